@@ -75,8 +75,11 @@ run_check() {
 }
 
 run_test() {
-#    cargo test -p utils --target "x86_64-unknown-linux-gnu"
-echo "no tests"
+    for LANGUAGE in "${LANGUAGES[@]}"
+    do
+        cargo test -p horloge-core --no-default-features --features "$LANGUAGE"
+        cargo test -p gen_front --no-default-features --features "$LANGUAGE"
+    done
 }
 
 run_build() {
@@ -0,0 +1,316 @@
+//! English grid and words.
+
+use crate::{Grid, Language, Phrase, Span};
+
+/// Grid of letters
+///
+/// "O'CLOCK" is spelled without its apostrophe to keep one letter per LED.
+pub const GRID: Grid = [
+    ['I', 'T', 'L', 'I', 'S', 'A', 'S', 'A', 'M', 'P', 'M'],
+    ['A', 'C', 'Q', 'U', 'A', 'R', 'T', 'E', 'R', 'D', 'C'],
+    ['T', 'W', 'E', 'N', 'T', 'Y', 'F', 'I', 'V', 'E', 'X'],
+    ['H', 'A', 'L', 'F', 'S', 'T', 'E', 'N', 'F', 'T', 'O'],
+    ['P', 'A', 'S', 'T', 'E', 'R', 'U', 'N', 'I', 'N', 'E'],
+    ['O', 'N', 'E', 'S', 'I', 'X', 'T', 'H', 'R', 'E', 'E'],
+    ['F', 'O', 'U', 'R', 'F', 'I', 'V', 'E', 'T', 'W', 'O'],
+    ['E', 'I', 'G', 'H', 'T', 'E', 'L', 'E', 'V', 'E', 'N'],
+    ['S', 'E', 'V', 'E', 'N', 'T', 'W', 'E', 'L', 'V', 'E'],
+    ['T', 'E', 'N', 'S', 'E', 'O', 'C', 'L', 'O', 'C', 'K'],
+];
+
+/// Language metadata
+pub const LANGUAGE: Language = Language {
+    name: "English",
+    code: "en",
+    grid: &GRID,
+};
+
+/// Words that can be lit on the grid
+///
+/// Words spelled the same but lit at different places, like the hour `TEN`
+/// and the minutes `TEN`, are distinct variants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Word {
+    It,
+    Is,
+    A,
+    Quarter,
+    Twenty,
+    FiveMinutes,
+    Half,
+    TenMinutes,
+    To,
+    Past,
+    Nine,
+    One,
+    Six,
+    Three,
+    Four,
+    Five,
+    Two,
+    Eight,
+    Eleven,
+    Seven,
+    Twelve,
+    Ten,
+    OClock,
+}
+
+impl Word {
+    /// All the words, in reading order
+    pub const ALL: [Word; 23] = [
+        Word::It,
+        Word::Is,
+        Word::A,
+        Word::Quarter,
+        Word::Twenty,
+        Word::FiveMinutes,
+        Word::Half,
+        Word::TenMinutes,
+        Word::To,
+        Word::Past,
+        Word::Nine,
+        Word::One,
+        Word::Six,
+        Word::Three,
+        Word::Four,
+        Word::Five,
+        Word::Two,
+        Word::Eight,
+        Word::Eleven,
+        Word::Seven,
+        Word::Twelve,
+        Word::Ten,
+        Word::OClock,
+    ];
+
+    /// Position of the word on the grid
+    pub const fn span(self) -> Span {
+        match self {
+            Word::It => Span::new(0, 0, 2),
+            Word::Is => Span::new(0, 3, 2),
+            Word::A => Span::new(1, 0, 1),
+            Word::Quarter => Span::new(1, 2, 7),
+            Word::Twenty => Span::new(2, 0, 6),
+            Word::FiveMinutes => Span::new(2, 6, 4),
+            Word::Half => Span::new(3, 0, 4),
+            Word::TenMinutes => Span::new(3, 5, 3),
+            Word::To => Span::new(3, 9, 2),
+            Word::Past => Span::new(4, 0, 4),
+            Word::Nine => Span::new(4, 7, 4),
+            Word::One => Span::new(5, 0, 3),
+            Word::Six => Span::new(5, 3, 3),
+            Word::Three => Span::new(5, 6, 5),
+            Word::Four => Span::new(6, 0, 4),
+            Word::Five => Span::new(6, 4, 4),
+            Word::Two => Span::new(6, 8, 3),
+            Word::Eight => Span::new(7, 0, 5),
+            Word::Eleven => Span::new(7, 5, 6),
+            Word::Seven => Span::new(8, 0, 5),
+            Word::Twelve => Span::new(8, 5, 6),
+            Word::Ten => Span::new(9, 0, 3),
+            Word::OClock => Span::new(9, 5, 6),
+        }
+    }
+
+    /// Text of the word, as spelled on the grid
+    pub const fn text(self) -> &'static str {
+        match self {
+            Word::It => "IT",
+            Word::Is => "IS",
+            Word::A => "A",
+            Word::Quarter => "QUARTER",
+            Word::Twenty => "TWENTY",
+            Word::Five | Word::FiveMinutes => "FIVE",
+            Word::Half => "HALF",
+            Word::Ten | Word::TenMinutes => "TEN",
+            Word::To => "TO",
+            Word::Past => "PAST",
+            Word::Nine => "NINE",
+            Word::One => "ONE",
+            Word::Six => "SIX",
+            Word::Three => "THREE",
+            Word::Four => "FOUR",
+            Word::Two => "TWO",
+            Word::Eight => "EIGHT",
+            Word::Eleven => "ELEVEN",
+            Word::Seven => "SEVEN",
+            Word::Twelve => "TWELVE",
+            Word::OClock => "OCLOCK",
+        }
+    }
+}
+
+/// Hours from twelve to eleven, indexed by `hour % 12`
+const HOURS: [Word; 12] = [
+    Word::Twelve,
+    Word::One,
+    Word::Two,
+    Word::Three,
+    Word::Four,
+    Word::Five,
+    Word::Six,
+    Word::Seven,
+    Word::Eight,
+    Word::Nine,
+    Word::Ten,
+    Word::Eleven,
+];
+
+/// Words to light to display `hour:minute`
+///
+/// `hour` is taken modulo 24 and `minute` modulo 60. Minutes are rounded
+/// down to a multiple of five, the remainder is stored in
+/// `Phrase::extra_minutes`.
+pub fn time_to_words(hour: u8, minute: u8) -> Phrase<Word> {
+    let minute = minute % 60;
+    let five = minute / 5;
+    // From "twenty five to" on, the time is told from the next hour
+    let hour = if five >= 7 {
+        (hour % 24 + 1) % 24
+    } else {
+        hour % 24
+    };
+
+    let mut phrase = Phrase::new();
+    phrase.extra_minutes = minute % 5;
+    phrase.push(Word::It);
+    phrase.push(Word::Is);
+    match five {
+        0 => {}
+        1 | 11 => phrase.push(Word::FiveMinutes),
+        2 | 10 => phrase.push(Word::TenMinutes),
+        3 | 9 => {
+            phrase.push(Word::A);
+            phrase.push(Word::Quarter);
+        }
+        4 | 8 => phrase.push(Word::Twenty),
+        5 | 7 => {
+            phrase.push(Word::Twenty);
+            phrase.push(Word::FiveMinutes);
+        }
+        _ => phrase.push(Word::Half),
+    }
+    match five {
+        0 => {}
+        1..=6 => phrase.push(Word::Past),
+        _ => phrase.push(Word::To),
+    }
+    phrase.push(HOURS[hour as usize % 12]);
+    if five == 0 {
+        phrase.push(Word::OClock);
+    }
+    phrase
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use std::string::String;
+    use std::vec::Vec;
+
+    fn spell(hour: u8, minute: u8) -> String {
+        time_to_words(hour, minute)
+            .iter()
+            .map(Word::text)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    #[test]
+    fn spans_match_grid() {
+        for word in Word::ALL {
+            let span = word.span();
+            let text: String = span.cells().map(|(row, col)| GRID[row][col]).collect();
+            assert_eq!(text, word.text(), "{:?} at {:?}", word, span);
+        }
+    }
+
+    #[test]
+    fn words_listed_in_reading_order() {
+        for pair in Word::ALL.windows(2) {
+            assert!(pair[0].span().start() <= pair[1].span().start());
+        }
+    }
+
+    #[test]
+    fn known_times() {
+        let cases = [
+            (0, 0, "IT IS TWELVE OCLOCK"),
+            (0, 5, "IT IS FIVE PAST TWELVE"),
+            (1, 10, "IT IS TEN PAST ONE"),
+            (2, 15, "IT IS A QUARTER PAST TWO"),
+            (3, 20, "IT IS TWENTY PAST THREE"),
+            (4, 25, "IT IS TWENTY FIVE PAST FOUR"),
+            (5, 30, "IT IS HALF PAST FIVE"),
+            (6, 35, "IT IS TWENTY FIVE TO SEVEN"),
+            (7, 40, "IT IS TWENTY TO EIGHT"),
+            (8, 45, "IT IS A QUARTER TO NINE"),
+            (9, 50, "IT IS TEN TO TEN"),
+            (10, 55, "IT IS FIVE TO ELEVEN"),
+            (11, 0, "IT IS ELEVEN OCLOCK"),
+            (12, 0, "IT IS TWELVE OCLOCK"),
+            (13, 3, "IT IS ONE OCLOCK"),
+            (23, 34, "IT IS HALF PAST ELEVEN"),
+            (23, 59, "IT IS FIVE TO TWELVE"),
+        ];
+        for (hour, minute, expected) in cases {
+            assert_eq!(spell(hour, minute), expected, "{}:{:02}", hour, minute);
+        }
+    }
+
+    #[test]
+    fn all_minutes() {
+        for hour in 0..24u8 {
+            for minute in 0..60u8 {
+                let phrase = time_to_words(hour, minute);
+                let words: Vec<Word> = phrase.iter().collect();
+                let time = std::format!("{}:{:02}", hour, minute);
+
+                assert_eq!(phrase.extra_minutes, minute % 5, "{}", time);
+                assert_eq!(&words[..2], &[Word::It, Word::Is], "{}", time);
+
+                // Words are lit left to right, top to bottom, without overlap
+                for pair in words.windows(2) {
+                    assert!(
+                        pair[0].span().end() <= pair[1].span().start(),
+                        "{}: {:?} overlaps or precedes {:?}",
+                        time,
+                        pair[1],
+                        pair[0]
+                    );
+                }
+
+                // Exactly one hour, told from the next hour past the half
+                let hours: Vec<Word> = words
+                    .iter()
+                    .copied()
+                    .filter(|w| HOURS.contains(w))
+                    .collect();
+                assert_eq!(hours.len(), 1, "{}", time);
+                let told = if minute >= 35 { hour + 1 } else { hour };
+                assert_eq!(hours[0], HOURS[told as usize % 12], "{}", time);
+
+                // "o'clock" only on the hour, "past" or "to" otherwise
+                assert_eq!(words.contains(&Word::OClock), minute < 5, "{}", time);
+                assert_eq!(
+                    words.contains(&Word::Past),
+                    (5..35).contains(&minute),
+                    "{}",
+                    time
+                );
+                assert_eq!(words.contains(&Word::To), minute >= 35, "{}", time);
+
+                // Same words for the whole five minutes
+                assert_eq!(
+                    spell(hour, minute),
+                    spell(hour, minute - minute % 5),
+                    "{}",
+                    time
+                );
+            }
+        }
+    }
+}
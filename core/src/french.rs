@@ -0,0 +1,360 @@
+//! French grid and words.
+
+use crate::{Grid, Language, Phrase, Span};
+
+/// Grid of letters
+pub const GRID: Grid = [
+    ['I', 'L', 'B', 'E', 'S', 'T', 'W', 'C', 'I', 'N', 'Q'],
+    ['D', 'E', 'U', 'X', 'S', 'E', 'P', 'T', 'U', 'N', 'E'],
+    ['Q', 'U', 'A', 'T', 'R', 'E', 'T', 'R', 'O', 'I', 'S'],
+    ['N', 'E', 'U', 'F', 'S', 'I', 'X', 'H', 'U', 'I', 'T'],
+    ['M', 'I', 'D', 'I', 'X', 'M', 'I', 'N', 'U', 'I', 'T'],
+    ['O', 'N', 'Z', 'E', 'J', 'H', 'E', 'U', 'R', 'E', 'S'],
+    ['L', 'M', 'O', 'I', 'N', 'S', 'K', 'C', 'I', 'N', 'Q'],
+    ['E', 'T', 'Y', 'D', 'I', 'X', 'D', 'E', 'M', 'I', 'E'],
+    ['M', 'V', 'I', 'N', 'G', 'T', '-', 'C', 'I', 'N', 'Q'],
+    ['D', 'L', 'E', 'R', 'Q', 'U', 'A', 'R', 'T', 'B', 'F'],
+];
+
+/// Language metadata
+pub const LANGUAGE: Language = Language {
+    name: "Français",
+    code: "fr",
+    grid: &GRID,
+};
+
+/// Words that can be lit on the grid
+///
+/// Words spelled the same but lit at different places, like the hour `DIX`
+/// and the minutes `DIX`, are distinct variants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Word {
+    Il,
+    Est,
+    Cinq,
+    Deux,
+    Sept,
+    Une,
+    Quatre,
+    Trois,
+    Neuf,
+    Six,
+    Huit,
+    Midi,
+    Dix,
+    Minuit,
+    Onze,
+    Heure,
+    Heures,
+    Moins,
+    CinqMinutes,
+    Et,
+    DixMinutes,
+    Demie,
+    Vingt,
+    VingtCinq,
+    Le,
+    Quart,
+}
+
+impl Word {
+    /// All the words, in reading order
+    pub const ALL: [Word; 26] = [
+        Word::Il,
+        Word::Est,
+        Word::Cinq,
+        Word::Deux,
+        Word::Sept,
+        Word::Une,
+        Word::Quatre,
+        Word::Trois,
+        Word::Neuf,
+        Word::Six,
+        Word::Huit,
+        Word::Midi,
+        Word::Dix,
+        Word::Minuit,
+        Word::Onze,
+        Word::Heure,
+        Word::Heures,
+        Word::Moins,
+        Word::CinqMinutes,
+        Word::Et,
+        Word::DixMinutes,
+        Word::Demie,
+        Word::Vingt,
+        Word::VingtCinq,
+        Word::Le,
+        Word::Quart,
+    ];
+
+    /// Position of the word on the grid
+    pub const fn span(self) -> Span {
+        match self {
+            Word::Il => Span::new(0, 0, 2),
+            Word::Est => Span::new(0, 3, 3),
+            Word::Cinq => Span::new(0, 7, 4),
+            Word::Deux => Span::new(1, 0, 4),
+            Word::Sept => Span::new(1, 4, 4),
+            Word::Une => Span::new(1, 8, 3),
+            Word::Quatre => Span::new(2, 0, 6),
+            Word::Trois => Span::new(2, 6, 5),
+            Word::Neuf => Span::new(3, 0, 4),
+            Word::Six => Span::new(3, 4, 3),
+            Word::Huit => Span::new(3, 7, 4),
+            Word::Midi => Span::new(4, 0, 4),
+            Word::Dix => Span::new(4, 2, 3),
+            Word::Minuit => Span::new(4, 5, 6),
+            Word::Onze => Span::new(5, 0, 4),
+            Word::Heure => Span::new(5, 5, 5),
+            Word::Heures => Span::new(5, 5, 6),
+            Word::Moins => Span::new(6, 1, 5),
+            Word::CinqMinutes => Span::new(6, 7, 4),
+            Word::Et => Span::new(7, 0, 2),
+            Word::DixMinutes => Span::new(7, 3, 3),
+            Word::Demie => Span::new(7, 6, 5),
+            Word::Vingt => Span::new(8, 1, 5),
+            Word::VingtCinq => Span::new(8, 1, 10),
+            Word::Le => Span::new(9, 1, 2),
+            Word::Quart => Span::new(9, 4, 5),
+        }
+    }
+
+    /// Text of the word, as spelled on the grid
+    pub const fn text(self) -> &'static str {
+        match self {
+            Word::Il => "IL",
+            Word::Est => "EST",
+            Word::Cinq | Word::CinqMinutes => "CINQ",
+            Word::Deux => "DEUX",
+            Word::Sept => "SEPT",
+            Word::Une => "UNE",
+            Word::Quatre => "QUATRE",
+            Word::Trois => "TROIS",
+            Word::Neuf => "NEUF",
+            Word::Six => "SIX",
+            Word::Huit => "HUIT",
+            Word::Midi => "MIDI",
+            Word::Dix | Word::DixMinutes => "DIX",
+            Word::Minuit => "MINUIT",
+            Word::Onze => "ONZE",
+            Word::Heure => "HEURE",
+            Word::Heures => "HEURES",
+            Word::Moins => "MOINS",
+            Word::Et => "ET",
+            Word::Demie => "DEMIE",
+            Word::Vingt => "VINGT",
+            Word::VingtCinq => "VINGT-CINQ",
+            Word::Le => "LE",
+            Word::Quart => "QUART",
+        }
+    }
+}
+
+/// Hours from one to eleven, indexed by `hour % 12`
+const HOURS: [Word; 12] = [
+    Word::Minuit,
+    Word::Une,
+    Word::Deux,
+    Word::Trois,
+    Word::Quatre,
+    Word::Cinq,
+    Word::Six,
+    Word::Sept,
+    Word::Huit,
+    Word::Neuf,
+    Word::Dix,
+    Word::Onze,
+];
+
+/// Words to light to display `hour:minute`
+///
+/// `hour` is taken modulo 24 and `minute` modulo 60. Minutes are rounded
+/// down to a multiple of five, the remainder is stored in
+/// `Phrase::extra_minutes`.
+pub fn time_to_words(hour: u8, minute: u8) -> Phrase<Word> {
+    let minute = minute % 60;
+    let five = minute / 5;
+    // From "moins vingt-cinq" on, the time is told from the next hour
+    let hour = if five >= 7 {
+        (hour % 24 + 1) % 24
+    } else {
+        hour % 24
+    };
+
+    let mut phrase = Phrase::new();
+    phrase.extra_minutes = minute % 5;
+    phrase.push(Word::Il);
+    phrase.push(Word::Est);
+    match hour {
+        0 => phrase.push(Word::Minuit),
+        12 => phrase.push(Word::Midi),
+        _ => {
+            let word = HOURS[hour as usize % 12];
+            phrase.push(word);
+            phrase.push(if word == Word::Une {
+                Word::Heure
+            } else {
+                Word::Heures
+            });
+        }
+    }
+    match five {
+        0 => {}
+        1 => phrase.push(Word::CinqMinutes),
+        2 => phrase.push(Word::DixMinutes),
+        3 => {
+            phrase.push(Word::Et);
+            phrase.push(Word::Quart);
+        }
+        4 => phrase.push(Word::Vingt),
+        5 => phrase.push(Word::VingtCinq),
+        6 => {
+            phrase.push(Word::Et);
+            phrase.push(Word::Demie);
+        }
+        7 => {
+            phrase.push(Word::Moins);
+            phrase.push(Word::VingtCinq);
+        }
+        8 => {
+            phrase.push(Word::Moins);
+            phrase.push(Word::Vingt);
+        }
+        9 => {
+            phrase.push(Word::Moins);
+            phrase.push(Word::Le);
+            phrase.push(Word::Quart);
+        }
+        10 => {
+            phrase.push(Word::Moins);
+            phrase.push(Word::DixMinutes);
+        }
+        _ => {
+            phrase.push(Word::Moins);
+            phrase.push(Word::CinqMinutes);
+        }
+    }
+    phrase
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use std::string::String;
+    use std::vec::Vec;
+
+    fn spell(hour: u8, minute: u8) -> String {
+        time_to_words(hour, minute)
+            .iter()
+            .map(Word::text)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    #[test]
+    fn spans_match_grid() {
+        for word in Word::ALL {
+            let span = word.span();
+            let text: String = span.cells().map(|(row, col)| GRID[row][col]).collect();
+            assert_eq!(text, word.text(), "{:?} at {:?}", word, span);
+        }
+    }
+
+    #[test]
+    fn words_listed_in_reading_order() {
+        for pair in Word::ALL.windows(2) {
+            assert!(pair[0].span().start() <= pair[1].span().start());
+        }
+    }
+
+    #[test]
+    fn known_times() {
+        let cases = [
+            (0, 0, "IL EST MINUIT"),
+            (0, 5, "IL EST MINUIT CINQ"),
+            (0, 30, "IL EST MINUIT ET DEMIE"),
+            (0, 35, "IL EST UNE HEURE MOINS VINGT-CINQ"),
+            (1, 0, "IL EST UNE HEURE"),
+            (1, 15, "IL EST UNE HEURE ET QUART"),
+            (2, 10, "IL EST DEUX HEURES DIX"),
+            (3, 20, "IL EST TROIS HEURES VINGT"),
+            (4, 25, "IL EST QUATRE HEURES VINGT-CINQ"),
+            (4, 40, "IL EST CINQ HEURES MOINS VINGT"),
+            (9, 45, "IL EST DIX HEURES MOINS LE QUART"),
+            (10, 50, "IL EST ONZE HEURES MOINS DIX"),
+            (11, 55, "IL EST MIDI MOINS CINQ"),
+            (12, 0, "IL EST MIDI"),
+            (12, 15, "IL EST MIDI ET QUART"),
+            (12, 45, "IL EST UNE HEURE MOINS LE QUART"),
+            (13, 0, "IL EST UNE HEURE"),
+            (18, 7, "IL EST SIX HEURES CINQ"),
+            (23, 34, "IL EST ONZE HEURES ET DEMIE"),
+            (23, 35, "IL EST MINUIT MOINS VINGT-CINQ"),
+            (23, 59, "IL EST MINUIT MOINS CINQ"),
+        ];
+        for (hour, minute, expected) in cases {
+            assert_eq!(spell(hour, minute), expected, "{}:{:02}", hour, minute);
+        }
+    }
+
+    #[test]
+    fn all_minutes() {
+        for hour in 0..24u8 {
+            for minute in 0..60u8 {
+                let phrase = time_to_words(hour, minute);
+                let words: Vec<Word> = phrase.iter().collect();
+                let time = std::format!("{}:{:02}", hour, minute);
+
+                assert_eq!(phrase.extra_minutes, minute % 5, "{}", time);
+                assert_eq!(&words[..2], &[Word::Il, Word::Est], "{}", time);
+
+                // Words are lit left to right, top to bottom, without overlap
+                for pair in words.windows(2) {
+                    assert!(
+                        pair[0].span().end() <= pair[1].span().start(),
+                        "{}: {:?} overlaps or precedes {:?}",
+                        time,
+                        pair[1],
+                        pair[0]
+                    );
+                }
+
+                // Exactly one hour, told from the next hour past the half
+                let hours: Vec<Word> = words
+                    .iter()
+                    .copied()
+                    .filter(|w| HOURS.contains(w) || *w == Word::Midi)
+                    .collect();
+                assert_eq!(hours.len(), 1, "{}", time);
+                let told = if minute >= 35 { (hour + 1) % 24 } else { hour };
+                let expected = match told {
+                    0 => Word::Minuit,
+                    12 => Word::Midi,
+                    h => HOURS[h as usize % 12],
+                };
+                assert_eq!(hours[0], expected, "{}", time);
+
+                // "heure" is singular after "une" and absent after midi/minuit
+                let suffix = words
+                    .iter()
+                    .find(|w| matches!(w, Word::Heure | Word::Heures));
+                match expected {
+                    Word::Midi | Word::Minuit => assert_eq!(suffix, None, "{}", time),
+                    Word::Une => assert_eq!(suffix, Some(&Word::Heure), "{}", time),
+                    _ => assert_eq!(suffix, Some(&Word::Heures), "{}", time),
+                }
+
+                // Same words for the whole five minutes
+                assert_eq!(
+                    spell(hour, minute),
+                    spell(hour, minute - minute % 5),
+                    "{}",
+                    time
+                );
+            }
+        }
+    }
+}
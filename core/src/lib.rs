@@ -0,0 +1,122 @@
+//! Letter grid and word table shared by the firmware and `gen_front`.
+//!
+//! Both binaries read the grid from here so that the letters cut in the
+//! front plate always match the LEDs lit by the firmware.
+
+#![no_std]
+
+pub mod english;
+pub mod french;
+
+/// Number of columns in the grid
+pub const GRID_WIDTH: usize = 11;
+/// Number of rows in the grid
+pub const GRID_HEIGHT: usize = 10;
+
+/// Grid of letters, indexed by row then column
+pub type Grid = [[char; GRID_WIDTH]; GRID_HEIGHT];
+
+/// Position of a word on the grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    /// Row of the word
+    pub row: usize,
+    /// Column of the first letter
+    pub col: usize,
+    /// Number of letters
+    pub len: usize,
+}
+
+impl Span {
+    /// Create a new span
+    pub const fn new(row: usize, col: usize, len: usize) -> Self {
+        Span { row, col, len }
+    }
+
+    /// Index of the first letter, in reading order
+    pub const fn start(&self) -> usize {
+        self.row * GRID_WIDTH + self.col
+    }
+
+    /// Index right after the last letter, in reading order
+    pub const fn end(&self) -> usize {
+        self.start() + self.len
+    }
+
+    /// Iterate over the `(row, column)` cells covered by the span
+    pub fn cells(&self) -> impl Iterator<Item = (usize, usize)> {
+        let row = self.row;
+        (self.col..self.col + self.len).map(move |col| (row, col))
+    }
+}
+
+/// Maximum number of words in a phrase
+pub const MAX_WORDS: usize = 8;
+
+/// Words to light to display a time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Phrase<W> {
+    words: [Option<W>; MAX_WORDS],
+    len: usize,
+    /// Minutes past the last multiple of five, shown by the minute markers
+    pub extra_minutes: u8,
+}
+
+impl<W: Copy> Phrase<W> {
+    /// Create an empty phrase
+    pub const fn new() -> Self {
+        Phrase {
+            words: [None; MAX_WORDS],
+            len: 0,
+            extra_minutes: 0,
+        }
+    }
+
+    /// Append a word to the phrase
+    ///
+    /// Panics if the phrase already holds `MAX_WORDS` words.
+    pub fn push(&mut self, word: W) {
+        assert!(self.len < MAX_WORDS, "Phrase is full");
+        self.words[self.len] = Some(word);
+        self.len += 1;
+    }
+
+    /// Number of words in the phrase
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the phrase has no word
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Iterate over the words, in reading order
+    pub fn iter(&self) -> impl Iterator<Item = W> + '_ {
+        self.words[..self.len].iter().flatten().copied()
+    }
+}
+
+impl<W: Copy> Default for Phrase<W> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Language metadata
+#[derive(Debug)]
+pub struct Language {
+    /// Name of the language, in that language
+    pub name: &'static str,
+    /// ISO 639-1 code
+    pub code: &'static str,
+    /// Grid of letters
+    pub grid: &'static Grid,
+}
+
+// English wins when both languages are enabled, so that `--features english`
+// works on top of the default features.
+#[cfg(feature = "english")]
+pub use english::{time_to_words, Word, GRID, LANGUAGE};
+#[cfg(all(feature = "french", not(feature = "english")))]
+pub use french::{time_to_words, Word, GRID, LANGUAGE};
@@ -2,11 +2,33 @@
 #![no_main]
 
 use embassy_executor::Spawner;
+use embassy_time::{Duration, Ticker};
+use horloge_core::{time_to_words, GRID_HEIGHT, GRID_WIDTH, LANGUAGE};
 
 use {defmt_rtt as _, panic_probe as _};
 
 #[embassy_executor::main]
 async fn main(_spawner: Spawner) {
     let _p = embassy_rp::init(Default::default());
-    defmt::info!("Hello, world!");
+    defmt::info!("Grid: {} ({}x{})", LANGUAGE.name, GRID_WIDTH, GRID_HEIGHT);
+
+    // No RTC yet: the clock starts at midnight on boot
+    let mut hour: u8 = 0;
+    let mut minute: u8 = 0;
+    let mut ticker = Ticker::every(Duration::from_secs(60));
+    loop {
+        let phrase = time_to_words(hour, minute);
+        defmt::info!("{=u8}:{=u8:02}", hour, minute);
+        for word in phrase.iter() {
+            defmt::info!("  {=str}", word.text());
+        }
+        defmt::info!("  +{=u8}", phrase.extra_minutes);
+
+        ticker.next().await;
+        minute += 1;
+        if minute == 60 {
+            minute = 0;
+            hour = (hour + 1) % 24;
+        }
+    }
 }
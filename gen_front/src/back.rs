@@ -0,0 +1,190 @@
+//! Back plate: the LED strips are stuck on it, behind the letters of the
+//! cover.
+
+use crate::cover::{generate_holes, generate_leds, Sizes};
+use crate::design::Design;
+use crate::path::Point;
+use crate::sheet::{Layer, LayerKind, Polygon, Rect, Shape, Sheet};
+use std::f64::consts::PI;
+
+/// Number of sides of the polygons drawing full circles
+const CIRCLE_SIDES: usize = 64;
+
+/// Points of the arc of `radius` around `center`, from angle `from` to `to`,
+/// counterclockwise as seen on the sheet
+///
+/// The angles are in radians, from the x axis towards the top of the sheet.
+fn arc(center: Point, radius: f64, from: f64, to: f64) -> impl Iterator<Item = Point> {
+    let steps = ((to - from).abs() / (2.0 * PI) * CIRCLE_SIDES as f64).ceil() as usize;
+    (0..=steps).map(move |i| {
+        let angle = from + (to - from) * i as f64 / steps as f64;
+        Point::new(
+            center.x + radius * angle.cos(),
+            center.y - radius * angle.sin(),
+        )
+    })
+}
+
+/// Keyhole whose round part is centered on `head`, its slot going up
+///
+/// The clock is hung by putting the keyhole over a screw head and letting it
+/// slide down, so that the screw ends up at the top of the slot.
+fn keyhole(head: Point, design: &Design) -> Shape {
+    let back = &design.back;
+    let radius = back.keyhole_diameter / 2.0;
+    let half_width = back.keyhole_width / 2.0;
+    // Where the edges of the slot meet the round part
+    let meet = (radius * radius - half_width * half_width)
+        .sqrt()
+        .atan2(half_width);
+    let end = Point::new(head.x, head.y - back.keyhole_length);
+    let mut points: Vec<Point> = arc(end, half_width, 0.0, PI).collect();
+    points.extend(arc(head, radius, PI - meet, 2.0 * PI + meet));
+    Shape::Polygon(Polygon { points })
+}
+
+/// Generate the keyholes, a quarter of the width from each side
+fn generate_keyholes(sizes: &Sizes, design: &Design) -> Vec<Shape> {
+    let back = &design.back;
+    let radius = back.keyhole_diameter / 2.0;
+    // Center the whole keyhole in the top margin
+    let extent = back.keyhole_length + back.keyhole_width / 2.0 + radius;
+    let y = (design.margin + extent) / 2.0 - radius;
+    [0.25, 0.75]
+        .iter()
+        .map(|f| keyhole(Point::new(sizes.width * f, y), design))
+        .collect()
+}
+
+/// Generate the slot for the cable, in the middle of the bottom margin
+fn generate_cable(sizes: &Sizes, design: &Design) -> Shape {
+    let back = &design.back;
+    Shape::Rect(Rect {
+        x: (sizes.width - back.cable_width) / 2.0,
+        y: sizes.height - (design.margin + back.cable_height) / 2.0,
+        width: back.cable_width,
+        height: back.cable_height,
+    })
+}
+
+/// Generate the back plate, the same size as the cover
+///
+/// The plate is drawn as seen from the front, like the cover, so that its
+/// mounting holes line up with the ones of the cover and the LED guides with
+/// the letters. The guides are meant to be engraved on the front face.
+pub fn generate(sizes: &Sizes, design: &Design) -> Sheet {
+    let mut layers = vec![Layer {
+        kind: LayerKind::Outline,
+        shapes: vec![Shape::Rect(Rect {
+            x: 0.0,
+            y: 0.0,
+            width: sizes.width,
+            height: sizes.height,
+        })],
+    }];
+    if design.holes.enabled {
+        layers.push(Layer {
+            kind: LayerKind::Holes,
+            shapes: generate_holes(sizes, design).into(),
+        });
+    }
+    layers.push(Layer {
+        kind: LayerKind::Leds,
+        shapes: generate_leds(sizes, design),
+    });
+    layers.push(Layer {
+        kind: LayerKind::Cable,
+        shapes: vec![generate_cable(sizes, design)],
+    });
+    if design.back.keyholes {
+        layers.push(Layer {
+            kind: LayerKind::Keyholes,
+            shapes: generate_keyholes(sizes, design),
+        });
+    }
+    for layer in &layers {
+        println!("{}:{}", layer.kind.name(), layer.shapes.len());
+    }
+    Sheet {
+        scale: sizes.scale,
+        layers,
+        width: sizes.width,
+        height: sizes.height,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::sizes;
+
+    fn layer(sheet: &Sheet, kind: LayerKind) -> Option<&[Shape]> {
+        sheet
+            .layers
+            .iter()
+            .find(|l| l.kind == kind)
+            .map(|l| l.shapes.as_slice())
+    }
+
+    #[test]
+    fn plate_matches_cover() {
+        let design = Design::default();
+        let sheet = generate(&sizes(), &design);
+        assert_eq!((sheet.width, sheet.height), (250.0, 250.0));
+        assert_eq!(
+            layer(&sheet, LayerKind::Leds).unwrap().len(),
+            design.width() * design.height()
+        );
+        // Same holes as the cover
+        let holes = layer(&sheet, LayerKind::Holes).unwrap();
+        for (hole, cover) in holes.iter().zip(generate_holes(&sizes(), &design)) {
+            match (hole, cover) {
+                (Shape::Circle(a), Shape::Circle(b)) => {
+                    assert_eq!((a.cx, a.cy, a.r), (b.cx, b.cy, b.r))
+                }
+                _ => panic!("holes must be circles"),
+            }
+        }
+        // Cable slot within the bottom margin
+        match layer(&sheet, LayerKind::Cable).unwrap() {
+            [Shape::Rect(r)] => {
+                assert!(r.y > 250.0 - design.margin && r.y + r.height < 250.0);
+                assert!((r.x + r.width / 2.0 - 125.0).abs() < 1e-9);
+            }
+            _ => panic!("cable slot must be a rectangle"),
+        }
+        assert!(layer(&sheet, LayerKind::Keyholes).is_none());
+    }
+
+    #[test]
+    fn keyholes_within_top_margin() {
+        let mut design = Design::default();
+        design.back.keyholes = true;
+        let sheet = generate(&sizes(), &design);
+        let keyholes = layer(&sheet, LayerKind::Keyholes).unwrap();
+        assert_eq!(keyholes.len(), 2);
+        let back = &design.back;
+        for shape in keyholes {
+            let Shape::Polygon(p) = shape else {
+                panic!("keyholes must be polygons");
+            };
+            let top = p.points.iter().map(|p| p.y).fold(f64::INFINITY, f64::min);
+            let bottom = p.points.iter().map(|p| p.y).fold(0.0, f64::max);
+            let extent =
+                back.keyhole_length + back.keyhole_width / 2.0 + back.keyhole_diameter / 2.0;
+            assert!((bottom - top - extent).abs() < 0.01);
+            assert!(((top + bottom) / 2.0 - design.margin / 2.0).abs() < 0.01);
+            // Narrow at the top, wide at the bottom
+            let width_at = |y: f64| {
+                let xs = p
+                    .points
+                    .iter()
+                    .filter(|p| (p.y - y).abs() < 0.5)
+                    .map(|p| p.x);
+                xs.clone().fold(0.0, f64::max) - xs.fold(f64::INFINITY, f64::min)
+            };
+            assert!(width_at(top + back.keyhole_width / 2.0) <= back.keyhole_width + 1e-9);
+            assert!(width_at(bottom - back.keyhole_diameter / 2.0) > back.keyhole_width);
+        }
+    }
+}
@@ -0,0 +1,198 @@
+//! Light baffle: slotted strips crossing between the LEDs, so that the light
+//! of an LED only reaches its letter.
+
+use crate::cover::{led_center, Sizes};
+use crate::design::Design;
+use crate::path::Point;
+use crate::sheet::{Layer, LayerKind, Polygon, Shape, Sheet};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Baffle walls of {wall}mm leave no room for LEDs of {led}mm")]
+    NoRoom { wall: f64, led: f64 },
+}
+
+/// Strip of `length` mm with slots of `width` mm at `slots`, cut from its top
+/// edge, `top` down when `from_top`, from the bottom edge otherwise
+///
+/// The strip is `height` high, its top left corner at `x`, `y` on the sheet.
+#[allow(clippy::too_many_arguments)]
+fn strip(
+    x: f64,
+    y: f64,
+    length: f64,
+    height: f64,
+    slots: &[f64],
+    width: f64,
+    depth: f64,
+    from_top: bool,
+) -> Shape {
+    // Slotted edge, left to right, then the plain edge, right to left
+    let (edge, other) = if from_top {
+        (0.0, height)
+    } else {
+        (height, 0.0)
+    };
+    let bottom = if from_top { depth } else { height - depth };
+    let mut points = vec![Point::new(0.0, edge)];
+    for slot in slots {
+        points.push(Point::new(slot - width / 2.0, edge));
+        points.push(Point::new(slot - width / 2.0, bottom));
+        points.push(Point::new(slot + width / 2.0, bottom));
+        points.push(Point::new(slot + width / 2.0, edge));
+    }
+    points.push(Point::new(length, edge));
+    points.push(Point::new(length, other));
+    points.push(Point::new(0.0, other));
+    Shape::Polygon(Polygon {
+        points: points
+            .into_iter()
+            .map(|p| Point::new(p.x + x, p.y + y))
+            .collect(),
+    })
+}
+
+/// Generate the strips of the baffle, laid out one under the other
+///
+/// There is a wall between every two LEDs and around the outer ones. The
+/// strips along the rows are slotted from the top and the ones along the
+/// columns from the bottom, each down to half the height, so that they fit
+/// into each other. Strips go past the outer walls by one thickness so that
+/// their end slots do not open them.
+pub fn generate(sizes: &Sizes, design: &Design) -> Result<Sheet, Error> {
+    let baffle = &design.baffle;
+    let t = baffle.thickness;
+    let slot = t + baffle.tolerance;
+    let h = baffle.height;
+    if slot >= sizes.led_spacing.min(sizes.row_spacing) - design.leds.size {
+        return Err(Error::NoRoom {
+            wall: slot,
+            led: design.leds.size,
+        });
+    }
+
+    // Walls between the columns and between the rows
+    let (x0, y0) = led_center(sizes, design, 0, 0);
+    let columns: Vec<f64> = (0..=design.width())
+        .map(|j| (j as f64 - 0.5) * sizes.led_spacing + x0)
+        .collect();
+    let rows: Vec<f64> = (0..=design.height())
+        .map(|i| (i as f64 - 0.5) * sizes.row_spacing + y0)
+        .collect();
+    let slots = |walls: &[f64]| -> Vec<f64> { walls.iter().map(|w| w - walls[0] + t).collect() };
+    let row_length = columns[columns.len() - 1] - columns[0] + 2.0 * t;
+    let column_length = rows[rows.len() - 1] - rows[0] + 2.0 * t;
+
+    let gap = baffle.gap;
+    let mut shapes = Vec::new();
+    let mut y = gap;
+    for _ in &rows {
+        shapes.push(strip(
+            gap,
+            y,
+            row_length,
+            h,
+            &slots(&columns),
+            slot,
+            h / 2.0,
+            true,
+        ));
+        y += h + gap;
+    }
+    for _ in &columns {
+        shapes.push(strip(
+            gap,
+            y,
+            column_length,
+            h,
+            &slots(&rows),
+            slot,
+            h / 2.0,
+            false,
+        ));
+        y += h + gap;
+    }
+    println!(
+        "Baffle: {} strips of {}mm and {} strips of {}mm",
+        rows.len(),
+        row_length,
+        columns.len(),
+        column_length
+    );
+
+    Ok(Sheet {
+        scale: sizes.scale,
+        layers: vec![Layer {
+            kind: LayerKind::Baffles,
+            shapes,
+        }],
+        width: row_length.max(column_length) + 2.0 * gap,
+        height: y,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::sizes;
+
+    fn polygons(sheet: &Sheet) -> Vec<&[Point]> {
+        sheet.layers[0]
+            .shapes
+            .iter()
+            .map(|s| match s {
+                Shape::Polygon(p) => p.points.as_slice(),
+                _ => panic!("strips must be polygons"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn strips_interlock() {
+        let design = Design::default();
+        let sheet = generate(&sizes(), &design).unwrap();
+        let strips = polygons(&sheet);
+        assert_eq!(strips.len(), design.height() + 1 + design.width() + 1);
+
+        let t = design.baffle.thickness;
+        let h = design.baffle.height;
+        let row = strips[0];
+        let column = strips[design.height() + 1];
+        // Plain edge plus four corners per slot
+        assert_eq!(row.len(), 4 + 4 * (design.width() + 1));
+        assert_eq!(column.len(), 4 + 4 * (design.height() + 1));
+
+        // Slots one LED apart along the rows, one row apart along the columns
+        let left_edges = |points: &[Point]| -> Vec<f64> {
+            points[1..points.len() - 3]
+                .chunks(4)
+                .map(|slot| slot[0].x)
+                .collect()
+        };
+        for pair in left_edges(row).windows(2) {
+            assert!((pair[1] - pair[0] - 17.0).abs() < 1e-9);
+        }
+        for pair in left_edges(column).windows(2) {
+            assert!((pair[1] - pair[0] - 21.0).abs() < 1e-9);
+        }
+        // The first slot leaves a thickness of material at the end
+        let slot = t + design.baffle.tolerance;
+        assert!((row[1].x - row[0].x - (t - slot / 2.0)).abs() < 1e-9);
+
+        // Slots of both strips meet in the middle
+        let row_depth = row[2].y - row[1].y;
+        let column_depth = column[1].y - column[2].y;
+        assert!((row_depth + column_depth - h).abs() < 1e-9);
+    }
+
+    #[test]
+    fn walls_too_thick() {
+        let mut design = Design::default();
+        design.baffle.thickness = 12.0;
+        assert!(matches!(
+            generate(&sizes(), &design),
+            Err(Error::NoRoom { led, .. }) if led == design.leds.size
+        ));
+    }
+}
@@ -1,52 +1,58 @@
 /// Module to generate the cover for the word clock.
-use crate::font::{FontAnalysis, FLOWER};
-
-// Letter N: 31.45mm width, 51mm height
-const LED_SIZE: f64 = 5f64;
-const LED_SPACING: f64 = 12f64 + LED_SIZE;
-
-const HOLE_DIAMETER: f64 = 3.3; // M4 according to
-                                // https://www.laserboost.com/design-guide-for-threaded-and-counterbored-components/
-
-const GRID_WIDTH: usize = 11;
-const GRID_HEIGHT: usize = 10;
-const MARGIN: f64 = 20.0;
-#[cfg(feature = "french")]
-const GRID: [[char; GRID_WIDTH]; GRID_HEIGHT] = [
-    ['I', 'L', 'B', 'E', 'S', 'T', 'W', 'C', 'I', 'N', 'Q'],
-    ['D', 'E', 'U', 'X', 'S', 'E', 'P', 'T', 'U', 'N', 'E'],
-    ['Q', 'U', 'A', 'T', 'R', 'E', 'T', 'R', 'O', 'I', 'S'],
-    ['N', 'E', 'U', 'F', 'S', 'I', 'X', 'H', 'U', 'I', 'T'],
-    ['M', 'I', 'D', 'I', 'X', 'M', 'I', 'N', 'U', 'I', 'T'],
-    ['O', 'N', 'Z', 'E', 'J', 'H', 'E', 'U', 'R', 'E', 'S'],
-    ['L', 'M', 'O', 'I', 'N', 'S', 'K', 'C', 'I', 'N', 'Q'],
-    ['E', 'T', 'Y', 'D', 'I', 'X', 'D', 'E', 'M', 'I', 'E'],
-    ['M', 'V', 'I', 'N', 'G', 'T', '-', 'C', 'I', 'N', 'Q'],
-    ['D', 'L', 'E', 'R', 'Q', 'U', 'A', 'R', 'T', 'B', 'F'],
-];
-#[cfg(feature = "english")]
-const GRID: [[char; GRID_WIDTH]; GRID_HEIGHT] = [
-    ['Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z'],
-    ['Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z'],
-    ['Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z'],
-    ['Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z'],
-    ['Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z'],
-    ['Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z'],
-    ['Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z'],
-    ['Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z'],
-    ['Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z'],
-    ['Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z', 'Z'],
-];
+use crate::design::{Alignment, Centering, Design};
+use crate::font::{self, FontAnalysis, Glyph};
+use crate::path;
+use crate::sheet::{Circle, Layer, LayerKind, Path, Rect, Shape, Sheet};
+use crate::stencil;
+use thiserror::Error;
 
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error(transparent)]
+    Font(#[from] font::Error),
+    #[error("Letters of the font have an invalid height to width ratio of {0}")]
+    LetterRatio(f64),
+    #[error("A grid of {cols}x{rows} letters with a letter ratio of {ratio} cannot be square")]
+    NotSquare {
+        cols: usize,
+        rows: usize,
+        ratio: f64,
+    },
+    #[error("A panel of {size}mm is too small for LEDs of {led}mm")]
+    TooSmall { size: f64, led: f64 },
+}
+
+/// Common LED strip densities, in LEDs per metre
+const STRIP_DENSITIES: [f64; 3] = [30.0, 60.0, 144.0];
+
+/// Dimensions of the cover, in mm
 #[derive(Debug)]
-struct Sizes {
-    width: f64,
-    height: f64,
-    scale: f64,
+pub struct Sizes {
+    pub width: f64,
+    pub height: f64,
+    /// Scale of the glyphs, from font units to mm
+    pub scale: f64,
+    /// Distance between the centers of two consecutive LEDs
+    pub led_spacing: f64,
+    /// Height of a letter
+    pub glyph_height: f64,
+    /// Distance between the centers of two consecutive rows
+    pub row_spacing: f64,
+}
+
+/// Height of the letters, from their baseline, that fills the letter height
+/// and is centered on the LEDs, in font units
+fn letter_height(font: &FontAnalysis, design: &Design) -> f64 {
+    match design.letters.alignment {
+        Alignment::Tallest => font.y_max,
+        Alignment::CapHeight => font.cap_height,
+        Alignment::XHeight => font.x_height,
+    }
+    .into()
 }
 
 impl Sizes {
-    fn compute(font: &FontAnalysis) -> Sizes {
+    pub fn compute(font: &FontAnalysis, design: &Design) -> Result<Sizes, Error> {
         // We need to compute the best font size so that the grid is square.
         // We know the horizontal spacing between the LEDs, the size of the
         // LEDs, and the ratio of the letters
@@ -57,21 +63,20 @@ impl Sizes {
         // - l: Width of a letter.
         // - h: Height of a letter, h = k * l.
         // - d: Spacing between the centers of consecutive LEDs.
-        // - k: Ratio of letter height to width, k = font.y_max / font.glyph_width_avg.
+        // - k: Ratio of letter height to width, k = letter_height / font.glyph_width_avg.
         // - H: Height of the grid in terms of number of letters.
         // - W: Width of the grid in terms of number of letters.
 
-        let d: f64 = LED_SPACING;
-        println!("LED spacing: {}", d);
-
-        let k: f64 = font.y_max as f64 / font.glyph_width_avg;
+        let k: f64 = letter_height(font, design) / font.glyph_width_avg;
         println!("Letter ratio (height/width): {}", k);
 
-        const H: f64 = GRID_HEIGHT as f64; // Number of rows in the grid
-        const W: f64 = GRID_WIDTH as f64; // Number of columns in the grid
+        let rows: f64 = design.height() as f64; // H, number of rows in the grid
+        let cols: f64 = design.width() as f64; // W, number of columns in the grid
 
         // Ensure variables are valid to avoid undefined behavior
-        assert!(k > 0.0, "Letter ratio (k) must be greater than 0.");
+        if !(k > 0.0 && k.is_finite()) {
+            return Err(Error::LetterRatio(k));
+        }
 
         // Derivation of the formula for `l`:
         // ----------------------------------------------------------
@@ -108,19 +113,48 @@ impl Sizes {
         //    d * (W - 1 - (H - 1) / k) = l * (H * k - (H - 1) / k - 1)
         //    Solving for `l`:
         //    l = d * (W - 1 - (H - 1) / k) / (H * k - (H - 1) / k - 1)
+        //
+        // 7. When fitting a panel of outer size `S` with margin `M`, `d` is
+        //    unknown. Writing l = d * r, with r the ratio above:
+        //    SqW = W * d - d + l = d * (W - 1 + r) = S - 2 * M
+        //    Solving for `d`:
+        //    d = (S - 2 * M) / (W - 1 + r)
         // ----------------------------------------------------------
 
         // Using the derived formula:
-        let numerator = d * (W - 1.0 - (H - 1.0) / k);
-        let denominator = H * k - (H - 1.0) / k - 1.0;
+        let numerator = cols - 1.0 - (rows - 1.0) / k;
+        let denominator = rows * k - (rows - 1.0) / k - 1.0;
 
-        // Check for potential division by zero
-        assert!(
-            denominator != 0.0,
-            "Denominator in the font size calculation must not be zero."
-        );
+        let r: f64 = numerator / denominator;
+
+        // Grids much taller than wide need letters wider than the LED
+        // spacing, or even of negative width, to be square. A zero
+        // denominator ends up there too.
+        if !(r > 0.0 && r < 1.0) {
+            return Err(Error::NotSquare {
+                cols: design.width(),
+                rows: design.height(),
+                ratio: k,
+            });
+        }
 
-        let l: f64 = numerator / denominator;
+        let d: f64 = match design.size {
+            Some(size) => {
+                let d = (size - 2.0 * design.margin) / (cols - 1.0 + r);
+                if d <= design.leds.size {
+                    return Err(Error::TooSmall {
+                        size,
+                        led: design.leds.size,
+                    });
+                }
+                check_strip_pitch(d, cols, design.leds.size);
+                d
+            }
+            None => design.leds.spacing,
+        };
+        println!("LED spacing: {}", d);
+
+        let l: f64 = d * r;
 
         // Output the computed letter width
         println!("Computed letter width (l): {}", l);
@@ -132,46 +166,121 @@ impl Sizes {
         println!("Letter width: {}", glyph_width);
         println!("Letter height: {}", glyph_width * k);
         println!("Letter spacing: horizontal {}, vertical {}", hspace, vspace);
-        let sq_width = W * glyph_width + (W - 1f64) * hspace;
-        let sq_height = H * glyph_width * k + (H - 1f64) * vspace;
+        let sq_width = cols * glyph_width + (cols - 1f64) * hspace;
+        let sq_height = rows * glyph_height + (rows - 1f64) * vspace;
         println!("Square width: {}", sq_width);
         println!("Square height: {}", sq_height);
 
-        let width = sq_width + 2f64 * MARGIN;
-        let height = sq_height + 2f64 * MARGIN;
+        let width = sq_width + 2f64 * design.margin;
+        let height = sq_height + 2f64 * design.margin;
 
-        let scale = glyph_height / font.y_max as f64;
-        Sizes {
+        let scale = glyph_height / letter_height(font, design);
+        Ok(Sizes {
             scale,
             width,
             height,
+            led_spacing: d,
+            glyph_height,
+            row_spacing: glyph_height + vspace,
+        })
+    }
+}
+
+/// Warn when the LED spacing `d` matches no common LED strip
+///
+/// A strip matches when the drift accumulated over a row of `cols` LEDs stays
+/// under half an LED.
+fn check_strip_pitch(d: f64, cols: f64, led_size: f64) {
+    let (density, pitch) = STRIP_DENSITIES
+        .iter()
+        .map(|n| (*n, 1000.0 / n))
+        .min_by(|a, b| (a.1 - d).abs().total_cmp(&(b.1 - d).abs()))
+        .unwrap();
+    let drift = (pitch - d).abs() * (cols - 1.0);
+    if drift > led_size / 2.0 {
+        eprintln!(
+            "Warning: no common LED strip is {:.2}mm apart, the closest ({} LEDs/m, {:.2}mm) drifts by {:.2}mm over a row",
+            d, density, pitch, drift
+        );
+    } else {
+        println!("LED spacing matches strips of {} LEDs/m", density);
+    }
+}
+
+/// Guide layers to generate along with the cover
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Guides {
+    /// Footprint of every LED
+    pub leds: bool,
+    /// Rectangle between the margins and the grid
+    pub margins: bool,
+    /// Bounding box of every letter
+    pub bounding_boxes: bool,
+}
+
+impl Guides {
+    /// Guides enabled by the `draw_*` features
+    pub fn from_features() -> Self {
+        Guides {
+            leds: cfg!(feature = "draw_leds"),
+            margins: cfg!(feature = "draw_margins"),
+            bounding_boxes: cfg!(feature = "draw_bounding_box"),
         }
     }
 }
 
-pub struct Circle {
-    pub cx: f64,
-    pub cy: f64,
-    pub r: f64,
+/// Center of the LED behind the letter at `row`, `col`
+pub fn led_center(doc: &Sizes, design: &Design, row: usize, col: usize) -> (f64, f64) {
+    let margin = design.margin;
+    let led_spacing = doc.led_spacing;
+    // Compute the horizontal offset to center the LEDs
+    let square_width = doc.width - 2.0 * margin;
+    let leds_width = (design.width() as f64 - 1.) * led_spacing;
+    let base_x = margin + (square_width - leds_width) / 2.;
+    // The rows are one letter height plus the vertical space apart
+    let base_y = margin + doc.glyph_height / 2.;
+    (
+        col as f64 * led_spacing + base_x,
+        row as f64 * doc.row_spacing + base_y,
+    )
 }
-pub struct Path {
-    pub d: String,
-    pub x: f64,
-    pub y: f64,
+
+/// Generate the footprint of every LED
+pub fn generate_leds(doc: &Sizes, design: &Design) -> Vec<Shape> {
+    let led_size = design.leds.size;
+    cells(design)
+        .map(|(row, col)| {
+            let (x, y) = led_center(doc, design, row, col);
+            Shape::Rect(Rect {
+                x: x - led_size / 2.0,
+                y: y - led_size / 2.0,
+                width: led_size,
+                height: led_size,
+            })
+        })
+        .collect()
 }
-pub enum Shape {
-    Circle(Circle),
-    Path(Path),
+
+/// Generate the rectangle between the margins and the grid
+fn generate_margins(doc: &Sizes, design: &Design) -> Shape {
+    let margin = design.margin;
+    Shape::Rect(Rect {
+        x: margin,
+        y: margin,
+        width: doc.width - 2.0 * margin,
+        height: doc.height - 2.0 * margin,
+    })
 }
 
 /// Draw the holes on each corner of the grid
-fn generate_holes(doc: &Sizes) -> [Shape; 4] {
-    let hole_radius = HOLE_DIAMETER / 2.0;
+pub fn generate_holes(doc: &Sizes, design: &Design) -> [Shape; 4] {
+    let hole_radius = design.holes.diameter / 2.0;
+    let margin = design.margin;
 
-    let x_left = MARGIN / 2.0 - hole_radius;
-    let x_right = doc.width - MARGIN / 2.0 - hole_radius;
-    let y_top = MARGIN / 2.0 + hole_radius;
-    let y_bottom = doc.height - hole_radius - MARGIN / 2.0;
+    let x_left = margin / 2.0 - hole_radius;
+    let x_right = doc.width - margin / 2.0 - hole_radius;
+    let y_top = margin / 2.0 + hole_radius;
+    let y_bottom = doc.height - hole_radius - margin / 2.0;
     [
         (x_left, y_top),
         (x_right, y_top),
@@ -188,9 +297,14 @@ fn generate_holes(doc: &Sizes) -> [Shape; 4] {
 }
 
 /// Generate the minutes on each side of the grid
-fn generate_minutes(font: &FontAnalysis, doc: &Sizes, scale: f64) -> [Shape; 4] {
-    let c: char = FLOWER;
-    let glyph = font.glyphs.get(&c).unwrap();
+fn generate_minutes(
+    font: &FontAnalysis,
+    doc: &Sizes,
+    scale: f64,
+    design: &Design,
+) -> Result<[Shape; 4], Error> {
+    let margin = design.margin;
+    let glyph = font.glyph(design.minutes.glyph)?;
     let path = glyph.path.clone();
     let x_min = glyph.bbox.x_min as f64;
     let x_max = glyph.bbox.x_max as f64;
@@ -199,13 +313,13 @@ fn generate_minutes(font: &FontAnalysis, doc: &Sizes, scale: f64) -> [Shape; 4]
     let mid_x = (glyph_width / 2.0 + x_min) * scale;
     let mid_y = glyph_height / 2.0 * scale;
 
-    let x_left = MARGIN / 2.0 - mid_x;
+    let x_left = margin / 2.0 - mid_x;
     let x_mid = doc.width / 2.0 - mid_x;
-    let x_right = doc.width - MARGIN / 2.0 - mid_x;
-    let y_top = MARGIN / 2.0 + mid_y;
+    let x_right = doc.width - margin / 2.0 - mid_x;
+    let y_top = margin / 2.0 + mid_y;
     let y_mid = doc.height / 2.0 + mid_y;
-    let y_bottom = doc.height - MARGIN / 2.0 + mid_y;
-    [
+    let y_bottom = doc.height - margin / 2.0 + mid_y;
+    Ok([
         (x_mid, y_top),
         (x_right, y_mid),
         (x_mid, y_bottom),
@@ -217,103 +331,443 @@ fn generate_minutes(font: &FontAnalysis, doc: &Sizes, scale: f64) -> [Shape; 4]
             x,
             y,
         })
-    })
+    }))
+}
+
+/// Largest error on the outlines of the letters when computing their
+/// centroid, in font units
+const CENTROID_TOLERANCE: f64 = 1.0;
+
+/// Horizontal middle of a glyph, in font units
+fn glyph_middle(glyph: &Glyph, centering: Centering) -> f64 {
+    let bbox_middle = (glyph.bbox.x_min as f64 + glyph.bbox.x_max as f64) / 2.0;
+    match centering {
+        Centering::BoundingBox => bbox_middle,
+        Centering::Centroid => path::centroid(&path::parse(&glyph.path), CENTROID_TOLERANCE)
+            .map_or(bbox_middle, |c| c.x),
+    }
+}
+
+/// Glyph of the letter at `row`, `col` and the position of its origin,
+/// centered on the LED behind it, then nudged as asked by the design
+fn place_glyph<'a>(
+    font: &'a FontAnalysis,
+    doc: &Sizes,
+    design: &Design,
+    row: usize,
+    col: usize,
+) -> Result<(&'a Glyph, f64, f64), Error> {
+    let scale = doc.scale;
+    let (led_x_mid_off, led_y_mid_off) = led_center(doc, design, row, col);
+    let y_glyph: f64 = led_y_mid_off + letter_height(font, design) / 2.0 * scale;
+
+    let c = design.grid[row][col];
+    let glyph = font.glyph(c)?;
+    let x_glyph = led_x_mid_off - glyph_middle(glyph, design.letters.centering) * scale;
+    let [dx, dy] = design.letters.nudges.get(&c).copied().unwrap_or_default();
+    Ok((glyph, x_glyph + dx, y_glyph + dy))
+}
+
+/// Every cell of the grid, in reading order
+fn cells(design: &Design) -> impl Iterator<Item = (usize, usize)> + '_ {
+    (0..design.height()).flat_map(move |row| (0..design.width()).map(move |col| (row, col)))
 }
 
 /// Generate the grid of letters
-fn generate_grid(
+fn generate_grid(font: &FontAnalysis, doc: &Sizes, design: &Design) -> Result<Vec<Shape>, Error> {
+    cells(design)
+        .map(|(row, col)| {
+            let (glyph, x, y) = place_glyph(font, doc, design, row, col)?;
+            Ok(Shape::Path(Path {
+                d: glyph.path.clone(),
+                x,
+                y,
+            }))
+        })
+        .collect()
+}
+
+/// Generate the bounding box of every letter of the grid
+///
+/// Glyphs are drawn with the y axis pointing up from their origin, so the top
+/// of the box is `y_max` above it.
+fn generate_bounding_boxes(
     font: &FontAnalysis,
     doc: &Sizes,
-    scale: f64,
-    render_bounding_boxes: bool,
-) -> Vec<Shape> {
-    // Compute the horizontal offset to center the LEDs
-    let square_width = doc.width - 2.0 * MARGIN;
-    let right_offset = (square_width - (GRID_WIDTH as f64 - 1.) * LED_SPACING - LED_SIZE) / 2.;
-    // Compute the vertical spacing between the LEDs
-    let square_height = doc.height - 2.0 * MARGIN;
-    let vert_spacing = square_height / (GRID_HEIGHT as f64);
-
-    let y_max = font.y_max as f64;
-
-    let base_y = MARGIN + vert_spacing / 2. - LED_SIZE / 2.;
-    let base_x = MARGIN + right_offset;
-    // Compute the vertical spacing between the LEDs
-    let square_height = doc.height - 2.0 * MARGIN;
-    let vert_spacing = square_height / (GRID_HEIGHT as f64);
-    let mut shapes: Vec<Shape> = Vec::new();
-
-    for (y, row) in GRID.iter().enumerate().take(GRID_HEIGHT) {
-        let led_y_mid_off = (y as f64 * vert_spacing) + base_y + (LED_SIZE / 2.0);
-        let y_glyph: f64 = led_y_mid_off + y_max / 2.0 * scale;
-
-        for (x, c) in row.iter().enumerate().take(GRID_WIDTH) {
-            let led_x_mid_off = (x as f64 * LED_SPACING) + base_x + (LED_SIZE / 2.0);
-
-            let glyph = font.glyphs.get(c).unwrap();
-            let path = glyph.path.clone();
-            let x_min = glyph.bbox.x_min as f64;
-            let x_max = glyph.bbox.x_max as f64;
-            let glyph_width = x_max - x_min;
-            let x_glyph = led_x_mid_off - (glyph_width / 2.0 + x_min) * scale;
-
-            let shape = if render_bounding_boxes {
-                let bb = &glyph.bbox;
-                let bbox_path = format!(
-                    "M {} {} L {} {} L {} {} L {} {} Z",
-                    bb.x_min, bb.y_min, bb.x_max, bb.y_min, bb.x_max, bb.y_max, bb.x_min, bb.y_max
-                );
-                Shape::Path(Path {
-                    d: bbox_path.clone(),
-                    x: x_glyph,
-                    y: y_glyph,
-                })
-            } else {
-                Shape::Path(Path {
-                    d: path.clone(),
-                    x: x_glyph,
-                    y: y_glyph,
-                })
-            };
-            shapes.push(shape);
+    design: &Design,
+) -> Result<Vec<Shape>, Error> {
+    let scale = doc.scale;
+    cells(design)
+        .map(|(row, col)| {
+            let (glyph, x, y) = place_glyph(font, doc, design, row, col)?;
+            let bb = &glyph.bbox;
+            Ok(Shape::Rect(Rect {
+                x: x + bb.x_min as f64 * scale,
+                y: y - bb.y_max as f64 * scale,
+                width: (bb.x_max - bb.x_min) as f64 * scale,
+                height: (bb.y_max - bb.y_min) as f64 * scale,
+            }))
+        })
+        .collect()
+}
+
+/// Largest error on the outlines of the letters getting bridges or clipped,
+/// in mm
+const BRIDGE_TOLERANCE: f64 = 0.01;
+
+/// Add stencil bridges to the counters of every glyph
+fn add_bridges(font: &mut FontAnalysis, doc: &Sizes, design: &Design) {
+    let width = design.stencil.bridge_width / doc.scale;
+    let tolerance = BRIDGE_TOLERANCE / doc.scale;
+    for (c, glyph) in font.glyphs.iter_mut() {
+        let (path, narrow) = stencil::bridge(&glyph.path, width, tolerance);
+        if narrow > 0 {
+            eprintln!(
+                "Warning: {} counter(s) of {:?} are too narrow for a bridge of {}mm",
+                narrow, c, design.stencil.bridge_width
+            );
         }
+        glyph.path = path;
     }
-    shapes
 }
 
-pub struct Cover {
-    pub scale: f64,
-    pub shapes: Vec<Shape>,
-    pub width: f64,
-    pub height: f64,
+/// Cut the letters of the grid going below their baseline further than half
+/// the space between two rows
+fn clip_descenders(font: &mut FontAnalysis, doc: &Sizes, design: &Design) {
+    let limit = -(doc.row_spacing - doc.glyph_height) / 2.0 / doc.scale;
+    let letters = design.characters();
+    for (c, glyph) in font.glyphs.iter_mut() {
+        if letters.contains(c) && f64::from(glyph.bbox.y_min) < limit {
+            println!("Clipping the descender of {:?}", c);
+            glyph.path = stencil::clip_below(&glyph.path, limit, BRIDGE_TOLERANCE / doc.scale);
+            glyph.bbox.y_min = limit.ceil() as i16;
+        }
+    }
 }
 
-impl Cover {
-    pub fn new(font: FontAnalysis) -> Self {
-        let sizes = Sizes::compute(&font);
-        println!("Document size: {}x{}", sizes.width, sizes.height);
-        let mut shapes: Vec<Shape> = Vec::new();
-        let scale = sizes.scale;
-        println!("scale:{}", scale);
-        let descender = font.descender as f64;
-        println!("descender:{}", descender);
-        let ascender = font.ascender as f64;
-        println!("ascender:{}", ascender);
-        //#[cfg(feature = "draw_leds")]
-        //draw_leds(writer, &sizes)?;
-        //#[cfg(feature = "draw_margins")]
-        //draw_margins(writer, &sizes)?;
-        shapes.extend(generate_holes(&sizes));
-        println!("shapes:{}", shapes.len());
-        shapes.extend(generate_minutes(&font, &sizes, scale));
-        println!("shapes:{}", shapes.len());
-        shapes.extend(generate_grid(&font, &sizes, scale, false));
-        println!("shapes:{}", shapes.len());
-        Cover {
-            scale,
-            shapes,
+/// Generate the cover: the letters, the minute markers and the holes
+pub fn generate(mut font: FontAnalysis, design: &Design, guides: Guides) -> Result<Sheet, Error> {
+    let sizes = Sizes::compute(&font, design)?;
+    println!("Document size: {}x{}", sizes.width, sizes.height);
+    if design.letters.clip_descenders {
+        clip_descenders(&mut font, &sizes, design);
+    }
+    if design.stencil.enabled {
+        add_bridges(&mut font, &sizes, design);
+    }
+    let mut layers: Vec<Layer> = vec![Layer {
+        kind: LayerKind::Outline,
+        shapes: vec![Shape::Rect(Rect {
+            x: 0.0,
+            y: 0.0,
             width: sizes.width,
             height: sizes.height,
+        })],
+    }];
+    let scale = sizes.scale;
+    println!("scale:{}", scale);
+    let descender = font.descender as f64;
+    println!("descender:{}", descender);
+    let ascender = font.ascender as f64;
+    println!("ascender:{}", ascender);
+    if design.holes.enabled {
+        layers.push(Layer {
+            kind: LayerKind::Holes,
+            shapes: generate_holes(&sizes, design).into(),
+        });
+    }
+    if design.minutes.enabled {
+        layers.push(Layer {
+            kind: LayerKind::Minutes,
+            shapes: generate_minutes(&font, &sizes, scale, design)?.into(),
+        });
+    }
+    layers.push(Layer {
+        kind: LayerKind::Letters,
+        shapes: generate_grid(&font, &sizes, design)?,
+    });
+    if guides.leds {
+        layers.push(Layer {
+            kind: LayerKind::Leds,
+            shapes: generate_leds(&sizes, design),
+        });
+    }
+    if guides.margins {
+        layers.push(Layer {
+            kind: LayerKind::Margins,
+            shapes: vec![generate_margins(&sizes, design)],
+        });
+    }
+    if guides.bounding_boxes {
+        layers.push(Layer {
+            kind: LayerKind::BoundingBoxes,
+            shapes: generate_bounding_boxes(&font, &sizes, design)?,
+        });
+    }
+    for layer in &layers {
+        println!("{}:{}", layer.kind.name(), layer.shapes.len());
+    }
+    Ok(Sheet {
+        scale,
+        layers,
+        width: sizes.width,
+        height: sizes.height,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::design::Minutes;
+    use crate::font::BoundingBox;
+    use std::collections::HashMap;
+
+    fn font() -> FontAnalysis {
+        FontAnalysis {
+            descender: -400,
+            ascender: 1600,
+            y_max: 1500,
+            cap_height: 1400,
+            x_height: 1000,
+            glyph_width_avg: 1200.0,
+            glyphs: HashMap::new(),
         }
     }
+
+    fn design(width: usize, height: usize) -> Design {
+        Design {
+            grid: vec![vec!['A'; width]; height],
+            ..Design::default()
+        }
+    }
+
+    #[test]
+    fn letters_fill_a_square() {
+        for (width, height) in [(11, 10), (12, 12), (13, 13), (12, 11), (11, 13)] {
+            let design = design(width, height);
+            let sizes = Sizes::compute(&font(), &design).unwrap();
+            let sq_width = sizes.width - 2.0 * design.margin;
+            let sq_height = sizes.height - 2.0 * design.margin;
+            let letters_height = (height - 1) as f64 * sizes.row_spacing + sizes.glyph_height;
+            assert!((sq_width - sq_height).abs() < 1e-9, "{}x{}", width, height);
+            assert!(
+                (letters_height - sq_height).abs() < 1e-9,
+                "{}x{}",
+                width,
+                height
+            );
+        }
+    }
+
+    #[test]
+    fn fit_to_size() {
+        for (width, height) in [(11, 10), (13, 13)] {
+            for size in [250.0, 350.0] {
+                let design = Design {
+                    size: Some(size),
+                    ..design(width, height)
+                };
+                let sizes = Sizes::compute(&font(), &design).unwrap();
+                assert!((sizes.width - size).abs() < 1e-9);
+                assert!((sizes.height - size).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn leds_are_centered() {
+        let design = design(11, 10);
+        let sizes = Sizes::compute(&font(), &design).unwrap();
+        let leds = generate_leds(&sizes, &design);
+        assert_eq!(leds.len(), 110);
+        let (Shape::Rect(first), Shape::Rect(last)) = (&leds[0], &leds[109]) else {
+            panic!("LEDs must be rectangles");
+        };
+        let left = first.x - design.margin;
+        let right = sizes.width - design.margin - (last.x + last.width);
+        assert!((left - right).abs() < 1e-9);
+        let top = first.y + first.height / 2.0 - design.margin;
+        let bottom = sizes.height - design.margin - (last.y + last.height / 2.0);
+        assert!((top - sizes.glyph_height / 2.0).abs() < 1e-9);
+        assert!((bottom - sizes.glyph_height / 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn bounding_boxes_are_centered_on_leds() {
+        let mut font = font();
+        font.glyphs.insert(
+            'A',
+            Glyph {
+                path: String::new(),
+                bbox: BoundingBox {
+                    x_min: 100,
+                    y_min: -200,
+                    x_max: 900,
+                    y_max: 1500,
+                },
+            },
+        );
+        let design = design(11, 10);
+        let sizes = Sizes::compute(&font, &design).unwrap();
+        let boxes = generate_bounding_boxes(&font, &sizes, &design).unwrap();
+        for ((row, col), shape) in cells(&design).zip(&boxes) {
+            let Shape::Rect(bb) = shape else {
+                panic!("bounding boxes must be rectangles");
+            };
+            let (x, y) = led_center(&sizes, &design, row, col);
+            assert!((bb.x + bb.width / 2.0 - x).abs() < 1e-9);
+            // The top of the tallest glyph is half a letter above the LED
+            assert!((bb.y + sizes.glyph_height / 2.0 - y).abs() < 1e-9);
+            assert!((bb.height - 1700.0 * sizes.scale).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn letters_are_centered_and_nudged() {
+        let mut font = font();
+        // L: vertical bar on the left, foot to the right
+        let bbox = BoundingBox {
+            x_min: 0,
+            y_min: 0,
+            x_max: 1000,
+            y_max: 1500,
+        };
+        font.glyphs.insert(
+            'L',
+            Glyph {
+                path: "M 0 0 L 1000 0 L 1000 200 L 200 200 L 200 1500 L 0 1500 Z".to_string(),
+                bbox,
+            },
+        );
+        let mut design = Design {
+            grid: vec![vec!['L'; 11]; 10],
+            ..Design::default()
+        };
+        let sizes = Sizes::compute(&font, &design).unwrap();
+        let (led_x, _) = led_center(&sizes, &design, 0, 0);
+        let (_, x, y) = place_glyph(&font, &sizes, &design, 0, 0).unwrap();
+        assert!((x + 500.0 * sizes.scale - led_x).abs() < 1e-9);
+
+        // Centroid of the area: 200x1500 bar and 800x200 foot
+        design.letters.centering = Centering::Centroid;
+        let (_, centroid_x, _) = place_glyph(&font, &sizes, &design, 0, 0).unwrap();
+        let middle = (300_000.0 * 100.0 + 160_000.0 * 600.0) / 460_000.0;
+        assert!((centroid_x + middle * sizes.scale - led_x).abs() < 1e-6);
+        assert!(centroid_x > x);
+
+        design.letters.nudges.insert('L', [0.5, -0.25]);
+        let (_, nudged_x, nudged_y) = place_glyph(&font, &sizes, &design, 0, 0).unwrap();
+        assert!((nudged_x - centroid_x - 0.5).abs() < 1e-9);
+        assert!((nudged_y - y + 0.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn cap_height_alignment() {
+        let mut font = font();
+        // Q going 300 units below the baseline
+        font.glyphs.insert(
+            'Q',
+            Glyph {
+                path: "M 0 -300 L 1000 -300 L 1000 1450 L 0 1450 Z".to_string(),
+                bbox: BoundingBox {
+                    x_min: 0,
+                    y_min: -300,
+                    x_max: 1000,
+                    y_max: 1450,
+                },
+            },
+        );
+        let mut design = Design {
+            grid: vec![vec!['Q'; 11]; 10],
+            ..Design::default()
+        };
+        design.letters.alignment = Alignment::CapHeight;
+        let sizes = Sizes::compute(&font, &design).unwrap();
+        assert!((sizes.scale * 1400.0 - sizes.glyph_height).abs() < 1e-9);
+        // The cap height is centered on the LED
+        let (_, led_y) = led_center(&sizes, &design, 0, 0);
+        let (_, _, y) = place_glyph(&font, &sizes, &design, 0, 0).unwrap();
+        assert!((y - 700.0 * sizes.scale - led_y).abs() < 1e-9);
+
+        design.letters.clip_descenders = true;
+        let limit = -(sizes.row_spacing - sizes.glyph_height) / 2.0 / sizes.scale;
+        assert!(limit > -300.0);
+        clip_descenders(&mut font, &sizes, &design);
+        let q = font.glyph('Q').unwrap();
+        let contours = path::parse(&q.path);
+        assert!(contours
+            .iter()
+            .flat_map(|c| c.flatten(1.0))
+            .all(|p| p.y >= limit - 1e-9));
+        assert!(f64::from(q.bbox.y_min) >= limit);
+    }
+
+    #[test]
+    fn too_tall() {
+        assert!(matches!(
+            Sizes::compute(&font(), &design(2, 30)),
+            Err(Error::NotSquare {
+                cols: 2,
+                rows: 30,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn missing_glyph() {
+        let design = design(11, 10);
+        let sizes = Sizes::compute(&font(), &design).unwrap();
+        assert!(matches!(
+            generate_grid(&font(), &sizes, &design),
+            Err(Error::Font(font::Error::MissingGlyph('A')))
+        ));
+    }
+
+    #[test]
+    fn outline_frames_the_cover() {
+        let mut font = font();
+        font.glyphs.insert(
+            'A',
+            Glyph {
+                path: "M 0 0 L 1000 0 L 1000 1500 L 0 1500 Z".to_string(),
+                bbox: BoundingBox {
+                    x_min: 0,
+                    y_min: 0,
+                    x_max: 1000,
+                    y_max: 1500,
+                },
+            },
+        );
+        let design = Design {
+            minutes: Minutes {
+                enabled: false,
+                ..Minutes::default()
+            },
+            ..design(11, 10)
+        };
+        let sheet = generate(font, &design, Guides::default()).unwrap();
+        let outline = &sheet.layers[0];
+        assert_eq!(outline.kind, LayerKind::Outline);
+        assert!(matches!(
+            outline.shapes[..],
+            [Shape::Rect(Rect { x, y, width, height })]
+                if x == 0.0 && y == 0.0 && width == sheet.width && height == sheet.height
+        ));
+
+        // First layer of the SVG file, a black frame of the size of the cover
+        let (width, height) = (sheet.width, sheet.height);
+        let file = std::env::temp_dir().join("gen_front_outline.svg");
+        crate::svg::generate(&file, sheet).unwrap();
+        let svg = std::fs::read_to_string(&file).unwrap();
+        std::fs::remove_file(&file).unwrap();
+        let lines: Vec<&str> = svg.lines().map(str::trim).collect();
+        let group = lines.iter().position(|l| l.starts_with("<g ")).unwrap();
+        assert!(lines[group].starts_with("<g id=\"outline\""));
+        let rect = format!(
+            "<rect x=\"0\" y=\"0\" width=\"{}\" height=\"{}\" stroke=\"black\"",
+            width, height
+        );
+        assert!(lines[group + 1].starts_with(&rect), "{}", lines[group + 1]);
+    }
 }
@@ -0,0 +1,621 @@
+//! Cover design: grid letters and hardware dimensions, loaded from TOML.
+
+use crate::font::FLOWER;
+use horloge_core::GRID;
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Cannot read design file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid design file: {0}")]
+    Parse(#[from] toml::de::Error),
+    #[error("Grid must have at least one row")]
+    EmptyGrid,
+    #[error("Grid row {row} must have {expected} letters, found {found}")]
+    GridWidth {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    #[error("Invalid {name}: {reason}")]
+    Parameter {
+        name: &'static str,
+        reason: &'static str,
+    },
+}
+
+/// LEDs behind the letters
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Leds {
+    /// Side of an LED, in mm
+    pub size: f64,
+    /// Distance between the centers of two consecutive LEDs, in mm
+    pub spacing: f64,
+}
+
+impl Default for Leds {
+    fn default() -> Self {
+        // Letter N: 31.45mm width, 51mm height
+        Leds {
+            size: 5.0,
+            spacing: 12.0 + 5.0,
+        }
+    }
+}
+
+/// Mounting holes, one in each corner
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Holes {
+    /// Whether to draw the holes
+    pub enabled: bool,
+    /// Diameter, in mm
+    pub diameter: f64,
+}
+
+impl Default for Holes {
+    fn default() -> Self {
+        Holes {
+            enabled: true,
+            // M4 according to
+            // https://www.laserboost.com/design-guide-for-threaded-and-counterbored-components/
+            diameter: 3.3,
+        }
+    }
+}
+
+/// Minute markers, one in the middle of each side
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Minutes {
+    /// Whether to draw the markers
+    pub enabled: bool,
+    /// Glyph of a marker
+    pub glyph: char,
+}
+
+impl Default for Minutes {
+    fn default() -> Self {
+        Minutes {
+            enabled: true,
+            glyph: FLOWER,
+        }
+    }
+}
+
+/// How the letters are centered horizontally on their LED
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Centering {
+    /// Middle of the bounding box of the glyph
+    #[default]
+    BoundingBox,
+    /// Centroid of the area of the glyph, looking centered even for letters
+    /// like L or T
+    Centroid,
+}
+
+/// Height the letters are sized and vertically centered by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Alignment {
+    /// Top of the tallest letter of the grid, accents and overshoots included
+    #[default]
+    Tallest,
+    /// Height of the capital letters of the font, so that flat letters line
+    /// up whatever the accents of the grid
+    CapHeight,
+    /// Height of the lowercase letters of the font, for lowercase grids
+    XHeight,
+}
+
+/// Position of the letters on their LED
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Letters {
+    /// How the letters are centered
+    pub centering: Centering,
+    /// Height the letters are sized by, from their baseline, centered on the
+    /// LEDs
+    pub alignment: Alignment,
+    /// Whether to cut the descenders of letters like Q or J halfway to the
+    /// next row
+    pub clip_descenders: bool,
+    /// Offset of some letters from their centered position, in mm, to the
+    /// right and down
+    pub nudges: BTreeMap<char, [f64; 2]>,
+}
+
+/// Bridges holding the counters of the letters, for covers cut through
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Stencil {
+    /// Whether to add the bridges
+    pub enabled: bool,
+    /// Width of a bridge, in mm
+    pub bridge_width: f64,
+}
+
+impl Default for Stencil {
+    fn default() -> Self {
+        Stencil {
+            enabled: false,
+            bridge_width: 1.0,
+        }
+    }
+}
+
+/// Egg-crate baffle behind the cover, keeping the light of each LED on its
+/// letter
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Baffle {
+    /// Thickness of the material, in mm
+    pub thickness: f64,
+    /// Play added to the width of the slots, in mm
+    pub tolerance: f64,
+    /// Height of the strips, distance between the LEDs and the cover, in mm
+    pub height: f64,
+    /// Distance between the strips on the sheet, in mm
+    pub gap: f64,
+}
+
+impl Default for Baffle {
+    fn default() -> Self {
+        Baffle {
+            thickness: 3.0,
+            tolerance: 0.1,
+            height: 20.0,
+            gap: 5.0,
+        }
+    }
+}
+
+/// Back plate, holding the LED strips
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Back {
+    /// Width of the slot for the USB cable, in the bottom margin, in mm
+    pub cable_width: f64,
+    /// Height of the slot for the USB cable, in mm
+    pub cable_height: f64,
+    /// Whether to cut keyholes to hang the clock, in the top margin
+    pub keyholes: bool,
+    /// Diameter of the round part of the keyholes, fitting a screw head, in mm
+    pub keyhole_diameter: f64,
+    /// Width of the slot of the keyholes, fitting a screw shank, in mm
+    pub keyhole_width: f64,
+    /// Distance between the centers of the round part and of the end of the
+    /// slot, in mm
+    pub keyhole_length: f64,
+}
+
+impl Default for Back {
+    fn default() -> Self {
+        Back {
+            cable_width: 12.0,
+            cable_height: 7.0,
+            keyholes: false,
+            keyhole_diameter: 8.0,
+            keyhole_width: 4.0,
+            keyhole_length: 6.0,
+        }
+    }
+}
+
+/// Finger-jointed box around the back plate, closed by the cover
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Enclosure {
+    /// Thickness of the material, in mm
+    pub thickness: f64,
+    /// Outer depth of the box, from the cover to the back, in mm
+    pub depth: f64,
+    /// Approximate width of the fingers, in mm
+    pub finger: f64,
+    /// Distance between the panels on the sheet, in mm
+    pub gap: f64,
+}
+
+impl Default for Enclosure {
+    fn default() -> Self {
+        Enclosure {
+            thickness: 3.0,
+            depth: 40.0,
+            finger: 10.0,
+            gap: 5.0,
+        }
+    }
+}
+
+/// Design file, as written by the user
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawDesign {
+    grid: Vec<String>,
+    size: Option<f64>,
+    margin: f64,
+    leds: Leds,
+    holes: Holes,
+    minutes: Minutes,
+    letters: Letters,
+    stencil: Stencil,
+    baffle: Baffle,
+    back: Back,
+    enclosure: Enclosure,
+}
+
+impl Default for RawDesign {
+    fn default() -> Self {
+        let design = Design::default();
+        RawDesign {
+            grid: design.grid.iter().map(|row| row.iter().collect()).collect(),
+            size: design.size,
+            margin: design.margin,
+            leds: design.leds,
+            holes: design.holes,
+            minutes: design.minutes,
+            letters: design.letters,
+            stencil: design.stencil,
+            baffle: design.baffle,
+            back: design.back,
+            enclosure: design.enclosure,
+        }
+    }
+}
+
+/// Cover design
+#[derive(Debug, Clone)]
+pub struct Design {
+    /// Grid of letters, indexed by row then column. Every row has the same
+    /// length.
+    pub grid: Vec<Vec<char>>,
+    /// Outer size of the cover, in mm. When set, the LED spacing is derived
+    /// from it instead of read from `leds.spacing`.
+    pub size: Option<f64>,
+    /// Distance between the grid and the edges of the cover, in mm
+    pub margin: f64,
+    /// LEDs
+    pub leds: Leds,
+    /// Mounting holes
+    pub holes: Holes,
+    /// Minute markers
+    pub minutes: Minutes,
+    /// Position of the letters
+    pub letters: Letters,
+    /// Stencil bridges
+    pub stencil: Stencil,
+    /// Light baffle
+    pub baffle: Baffle,
+    /// Back plate
+    pub back: Back,
+    /// Box
+    pub enclosure: Enclosure,
+}
+
+impl Default for Design {
+    fn default() -> Self {
+        Design {
+            grid: GRID.iter().map(|row| row.to_vec()).collect(),
+            size: None,
+            margin: 20.0,
+            leds: Leds::default(),
+            holes: Holes::default(),
+            minutes: Minutes::default(),
+            letters: Letters::default(),
+            stencil: Stencil::default(),
+            baffle: Baffle::default(),
+            back: Back::default(),
+            enclosure: Enclosure::default(),
+        }
+    }
+}
+
+fn check(valid: bool, name: &'static str, reason: &'static str) -> Result<(), Error> {
+    if valid {
+        Ok(())
+    } else {
+        Err(Error::Parameter { name, reason })
+    }
+}
+
+impl Design {
+    /// Number of columns of the grid
+    pub fn width(&self) -> usize {
+        self.grid.first().map_or(0, Vec::len)
+    }
+
+    /// Number of rows of the grid
+    pub fn height(&self) -> usize {
+        self.grid.len()
+    }
+
+    /// Every character of the grid, once
+    pub fn characters(&self) -> BTreeSet<char> {
+        self.grid.iter().flatten().copied().collect()
+    }
+
+    /// Load a design from a TOML file
+    ///
+    /// Missing fields take the value of the built-in design.
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let text = std::fs::read_to_string(path)?;
+        Self::parse(&text)
+    }
+
+    /// Fit the cover in a square of `size` mm, deriving the LED spacing
+    pub fn fit_to_size(&mut self, size: f64) -> Result<(), Error> {
+        self.size = Some(size);
+        self.check()
+    }
+
+    /// Add stencil bridges to the letters
+    pub fn enable_stencil(&mut self) {
+        self.stencil.enabled = true;
+    }
+
+    /// Parse a design from TOML
+    pub fn parse(text: &str) -> Result<Self, Error> {
+        let raw: RawDesign = toml::from_str(text)?;
+
+        let grid: Vec<Vec<char>> = raw.grid.iter().map(|row| row.chars().collect()).collect();
+        let width = match grid.first() {
+            Some(first) if !first.is_empty() => first.len(),
+            _ => return Err(Error::EmptyGrid),
+        };
+        for (row, letters) in grid.iter().enumerate() {
+            if letters.len() != width {
+                return Err(Error::GridWidth {
+                    row,
+                    expected: width,
+                    found: letters.len(),
+                });
+            }
+        }
+
+        let design = Design {
+            grid,
+            size: raw.size,
+            margin: raw.margin,
+            leds: raw.leds,
+            holes: raw.holes,
+            minutes: raw.minutes,
+            letters: raw.letters,
+            stencil: raw.stencil,
+            baffle: raw.baffle,
+            back: raw.back,
+            enclosure: raw.enclosure,
+        };
+        design.check()?;
+        Ok(design)
+    }
+
+    /// Check the dimensions are consistent
+    fn check(&self) -> Result<(), Error> {
+        check(
+            self.grid.iter().flatten().all(|c| !c.is_whitespace()),
+            "grid",
+            "letters must not be blank",
+        )?;
+        check(
+            self.margin > 0.0 && self.margin.is_finite(),
+            "margin",
+            "must be positive",
+        )?;
+        check(
+            self.size
+                .map_or(true, |size| size > 2.0 * self.margin && size.is_finite()),
+            "size",
+            "must be larger than twice the margin",
+        )?;
+        check(
+            self.leds.size > 0.0 && self.leds.size.is_finite(),
+            "leds.size",
+            "must be positive",
+        )?;
+        check(
+            self.leds.spacing > self.leds.size && self.leds.spacing.is_finite(),
+            "leds.spacing",
+            "must be larger than leds.size",
+        )?;
+        check(
+            self.holes.diameter > 0.0 && self.holes.diameter.is_finite(),
+            "holes.diameter",
+            "must be positive",
+        )?;
+        check(
+            !self.holes.enabled || self.holes.diameter < self.margin / 2.0,
+            "holes.diameter",
+            "must be smaller than half the margin",
+        )?;
+        let letters = self.characters();
+        check(
+            self.letters.nudges.keys().all(|c| letters.contains(c)),
+            "letters.nudges",
+            "must only move letters of the grid",
+        )?;
+        check(
+            self.letters
+                .nudges
+                .values()
+                .flatten()
+                .all(|v| v.is_finite()),
+            "letters.nudges",
+            "must be numbers",
+        )?;
+        check(
+            self.stencil.bridge_width > 0.0 && self.stencil.bridge_width.is_finite(),
+            "stencil.bridge_width",
+            "must be positive",
+        )?;
+        check(
+            self.baffle.thickness > 0.0 && self.baffle.thickness.is_finite(),
+            "baffle.thickness",
+            "must be positive",
+        )?;
+        check(
+            self.baffle.tolerance >= 0.0 && self.baffle.tolerance.is_finite(),
+            "baffle.tolerance",
+            "must not be negative",
+        )?;
+        check(
+            self.baffle.height > 0.0 && self.baffle.height.is_finite(),
+            "baffle.height",
+            "must be positive",
+        )?;
+        check(
+            self.baffle.gap >= 0.0 && self.baffle.gap.is_finite(),
+            "baffle.gap",
+            "must not be negative",
+        )?;
+        check(
+            self.back.cable_width > 0.0
+                && self.back.cable_width.is_finite()
+                && self.back.cable_height > 0.0,
+            "back.cable_width",
+            "must be positive, as well as back.cable_height",
+        )?;
+        check(
+            self.back.cable_height < self.margin,
+            "back.cable_height",
+            "must be smaller than the margin",
+        )?;
+        check(
+            self.back.keyhole_width > 0.0
+                && self.back.keyhole_width < self.back.keyhole_diameter
+                && self.back.keyhole_diameter.is_finite(),
+            "back.keyhole_width",
+            "must be positive and smaller than back.keyhole_diameter",
+        )?;
+        check(
+            self.back.keyhole_length.is_finite()
+                && (!self.back.keyholes
+                    || self.back.keyhole_length + self.back.keyhole_diameter < self.margin),
+            "back.keyhole_length",
+            "must leave the keyholes within the margin",
+        )?;
+        check(
+            self.enclosure.thickness > 0.0 && self.enclosure.thickness.is_finite(),
+            "enclosure.thickness",
+            "must be positive",
+        )?;
+        check(
+            self.enclosure.finger > 2.0 * self.enclosure.thickness
+                && self.enclosure.finger.is_finite(),
+            "enclosure.finger",
+            "must be larger than twice enclosure.thickness",
+        )?;
+        check(
+            self.enclosure.depth >= self.enclosure.finger && self.enclosure.depth.is_finite(),
+            "enclosure.depth",
+            "must be larger than enclosure.finger",
+        )?;
+        check(
+            self.enclosure.gap >= 0.0 && self.enclosure.gap.is_finite(),
+            "enclosure.gap",
+            "must not be negative",
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn letter_nudges() {
+        let design = Design::parse(
+            "grid = [\"LTJ\"]\n[letters]\ncentering = \"centroid\"\nnudges = { L = [0.5, -0.25] }",
+        )
+        .unwrap();
+        assert_eq!(design.letters.centering, Centering::Centroid);
+        assert_eq!(design.letters.alignment, Alignment::Tallest);
+        assert_eq!(design.letters.nudges.get(&'L'), Some(&[0.5, -0.25]));
+        assert!(matches!(
+            Design::parse("grid = [\"LTJ\"]\n[letters]\nnudges = { Q = [0.5, 0.0] }"),
+            Err(Error::Parameter {
+                name: "letters.nudges",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn characters_of_the_grid() {
+        let design = Design::parse("grid = [\"ÉTÉ\", \"D'O\"]").unwrap();
+        assert_eq!(design.characters().into_iter().collect::<String>(), "'DOTÉ");
+    }
+
+    #[test]
+    fn empty_file_is_builtin_design() {
+        let design = Design::parse("").unwrap();
+        assert_eq!(design.grid, Design::default().grid);
+        assert_eq!(design.margin, Design::default().margin);
+    }
+
+    #[test]
+    fn example_file() {
+        let design = Design::parse(include_str!("../designs/french.toml")).unwrap();
+        assert_eq!(design.width(), 11);
+        assert_eq!(design.height(), 10);
+        assert_eq!(design.grid[9], horloge_core::french::GRID[9]);
+        assert_eq!(design.leds.spacing, 17.0);
+    }
+
+    #[test]
+    fn ragged_grid() {
+        let mut rows: Vec<String> = GRID.iter().map(|r| r.iter().collect()).collect();
+        rows[3].pop();
+        let text = format!("grid = {:?}", rows);
+        assert!(matches!(
+            Design::parse(&text),
+            Err(Error::GridWidth { row: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn non_square_grid() {
+        let design = Design::parse("grid = [\"ABCDEFGHIJKL\", \"MNOPQRSTUVWX\"]").unwrap();
+        assert_eq!(design.width(), 12);
+        assert_eq!(design.height(), 2);
+        assert!(matches!(Design::parse("grid = []"), Err(Error::EmptyGrid)));
+    }
+
+    #[test]
+    fn invalid_parameters() {
+        assert!(matches!(
+            Design::parse("[leds]\nsize = 20.0\nspacing = 10.0"),
+            Err(Error::Parameter {
+                name: "leds.spacing",
+                ..
+            })
+        ));
+        assert!(matches!(
+            Design::parse("margin = \"wide\"\n"),
+            Err(Error::Parse(_))
+        ));
+        assert!(matches!(
+            Design::parse("unknown = 1.0\n"),
+            Err(Error::Parse(_))
+        ));
+        // Infinite sizes pass every comparison but cannot be drawn
+        for (text, field) in [
+            ("size = inf\n", "size"),
+            ("margin = inf\n", "margin"),
+            ("[leds]\nspacing = inf\n", "leds.spacing"),
+            ("[back]\nkeyhole_length = inf\n", "back.keyhole_length"),
+            ("[enclosure]\ndepth = inf\n", "enclosure.depth"),
+        ] {
+            match Design::parse(text) {
+                Err(Error::Parameter { name, .. }) => assert_eq!(name, field),
+                other => panic!("{}: {:?}", text, other),
+            }
+        }
+    }
+}
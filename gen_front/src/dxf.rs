@@ -1,14 +1,37 @@
-/// Generates a DXF file for the given cover design.
-use crate::cover::{Circle, Cover, Path, Shape};
+//! Generates a DXF file for the given sheet.
+
+use crate::path::{self, Contour, Point};
+use crate::sheet::{Circle, LayerKind, Path, Shape, Sheet};
 use std::fs::File;
 use std::io::Result as IoResult;
 use std::io::{BufWriter, Write};
 use std::path::PathBuf;
 
+/// How glyph outlines are written
 #[derive(Debug, Clone, Copy)]
-struct Point {
-    x: f64,
-    y: f64,
+pub enum Curves {
+    /// Exact cubic B-splines
+    Splines,
+    /// Closed polylines staying within `tolerance` mm of the outlines, for
+    /// the tools that do not read splines
+    Polylines { tolerance: f64 },
+}
+
+/// AutoCAD Color Index of the layers, so that cut settings can be assigned
+/// per color
+fn layer_color(kind: LayerKind) -> u8 {
+    match kind {
+        LayerKind::Outline => 7,       // White, or black on a white background
+        LayerKind::Holes => 1,         // Red
+        LayerKind::Minutes => 5,       // Blue
+        LayerKind::Letters => 3,       // Green
+        LayerKind::Leds => 6,          // Magenta
+        LayerKind::Margins => 4,       // Cyan
+        LayerKind::BoundingBoxes => 8, // Gray
+        LayerKind::Baffles => 2,       // Yellow
+        LayerKind::Cable => 30,        // Orange
+        LayerKind::Keyholes => 200,    // Violet
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -27,6 +50,12 @@ fn write_dxf_header(writer: &mut BufWriter<File>, width: f64, height: f64) -> Io
     writeln!(writer, "2")?;
     writeln!(writer, "HEADER")?;
 
+    // AutoCAD 2000, the first version with SPLINE and LWPOLYLINE entities
+    writeln!(writer, "9")?;
+    writeln!(writer, "$ACADVER")?;
+    writeln!(writer, "1")?;
+    writeln!(writer, "AC1015")?;
+
     // $INSUNITS to millimeters
     writeln!(writer, "9")?;
     writeln!(writer, "$INSUNITS")?;
@@ -73,7 +102,23 @@ fn write_dxf_header(writer: &mut BufWriter<File>, width: f64, height: f64) -> Io
     Ok(())
 }
 
-fn write_dxf_tables(writer: &mut BufWriter<File>) -> IoResult<()> {
+/// Declare a layer in the layer table
+fn write_layer(writer: &mut BufWriter<File>, name: &str, color: u8) -> IoResult<()> {
+    writeln!(writer, "0")?;
+    writeln!(writer, "LAYER")?;
+    writeln!(writer, "2")?; // Name
+    writeln!(writer, "{}", name)?;
+    writeln!(writer, "70")?; // Flags
+    writeln!(writer, "0")?;
+    writeln!(writer, "62")?; // Color
+    writeln!(writer, "{}", color)?;
+    writeln!(writer, "6")?; // Line type
+    writeln!(writer, "CONTINUOUS")?;
+
+    Ok(())
+}
+
+fn write_dxf_tables(writer: &mut BufWriter<File>, layers: &[(&str, u8)]) -> IoResult<()> {
     writeln!(writer, "0")?;
     writeln!(writer, "SECTION")?;
     writeln!(writer, "2")?;
@@ -84,20 +129,14 @@ fn write_dxf_tables(writer: &mut BufWriter<File>) -> IoResult<()> {
     writeln!(writer, "TABLE")?;
     writeln!(writer, "2")?;
     writeln!(writer, "LAYER")?;
-    writeln!(writer, "70")?;
-    writeln!(writer, "1")?;
+    writeln!(writer, "70")?; // Number of layers
+    writeln!(writer, "{}", layers.len() + 1)?;
 
     // Default layer
-    writeln!(writer, "0")?;
-    writeln!(writer, "LAYER")?;
-    writeln!(writer, "2")?;
-    writeln!(writer, "0")?;
-    writeln!(writer, "70")?;
-    writeln!(writer, "0")?;
-    writeln!(writer, "62")?;
-    writeln!(writer, "7")?;
-    writeln!(writer, "6")?;
-    writeln!(writer, "CONTINUOUS")?;
+    write_layer(writer, "0", 7)?;
+    for (name, color) in layers {
+        write_layer(writer, name, *color)?;
+    }
 
     writeln!(writer, "0")?;
     writeln!(writer, "ENDTAB")?;
@@ -130,16 +169,17 @@ fn write_dxf_footer(writer: &mut BufWriter<File>) -> IoResult<()> {
     Ok(())
 }
 
-/// Write a circle in DXF format
-fn write_circle(writer: &mut BufWriter<File>, c: Circle) -> IoResult<()> {
+/// Write a circle in DXF format, the y axis going up from the bottom of a
+/// sheet `height` high
+fn write_circle(writer: &mut BufWriter<File>, layer: &str, height: f64, c: Circle) -> IoResult<()> {
     writeln!(writer, "0")?;
     writeln!(writer, "CIRCLE")?;
     writeln!(writer, "8")?; // Layer
-    writeln!(writer, "0")?;
+    writeln!(writer, "{}", layer)?;
     writeln!(writer, "10")?; // Center X
     writeln!(writer, "{}", c.cx)?;
     writeln!(writer, "20")?; // Center Y
-    writeln!(writer, "{}", c.cy)?;
+    writeln!(writer, "{}", height - c.cy)?;
     writeln!(writer, "30")?; // Center Z
     writeln!(writer, "0.0")?;
     writeln!(writer, "40")?; // Radius
@@ -151,26 +191,34 @@ fn write_circle(writer: &mut BufWriter<File>, c: Circle) -> IoResult<()> {
 /// Write a rectangle as 4 lines in DXF format
 fn write_rectangle(
     writer: &mut BufWriter<File>,
+    layer: &str,
     x: f64,
     y: f64,
     width: f64,
     height: f64,
 ) -> IoResult<()> {
     // Draw rectangle as 4 lines (bottom, right, top, left)
-    write_line(writer, x, y, x + width, y)?; // Bottom
-    write_line(writer, x + width, y, x + width, y + height)?; // Right
-    write_line(writer, x + width, y + height, x, y + height)?; // Top
-    write_line(writer, x, y + height, x, y)?; // Left
+    write_line(writer, layer, x, y, x + width, y)?; // Bottom
+    write_line(writer, layer, x + width, y, x + width, y + height)?; // Right
+    write_line(writer, layer, x + width, y + height, x, y + height)?; // Top
+    write_line(writer, layer, x, y + height, x, y)?; // Left
 
     Ok(())
 }
 
 /// Write a line in DXF format
-fn write_line(writer: &mut BufWriter<File>, x1: f64, y1: f64, x2: f64, y2: f64) -> IoResult<()> {
+fn write_line(
+    writer: &mut BufWriter<File>,
+    layer: &str,
+    x1: f64,
+    y1: f64,
+    x2: f64,
+    y2: f64,
+) -> IoResult<()> {
     writeln!(writer, "0")?;
     writeln!(writer, "LINE")?;
     writeln!(writer, "8")?; // Layer
-    writeln!(writer, "0")?;
+    writeln!(writer, "{}", layer)?;
     writeln!(writer, "10")?; // Start X
     writeln!(writer, "{}", x1)?;
     writeln!(writer, "20")?; // Start Y
@@ -187,109 +235,57 @@ fn write_line(writer: &mut BufWriter<File>, x1: f64, y1: f64, x2: f64, y2: f64)
     Ok(())
 }
 
-fn parse_svg_path(path_data: &str, transform: &Matrix) -> Vec<Vec<Point>> {
-    let mut points = Vec::new();
-    let tokens: Vec<&str> = path_data.split_whitespace().collect();
-    let mut i = 0;
-    let mut splines = Vec::new();
-
-    while i < tokens.len() {
-        let cmd = tokens[i];
-        i += 1;
-
-        match cmd {
-            "M" => {
-                // Move to
-                if i + 1 < tokens.len() {
-                    let x: f64 = tokens[i].parse().unwrap_or(0.0);
-                    let y: f64 = tokens[i + 1].parse().unwrap_or(0.0);
-                    points.push(transform_point(x, y, transform));
-                    i += 2;
-                }
-            }
-            "L" => {
-                // Line to
-                if i + 1 < tokens.len() {
-                    let x: f64 = tokens[i].parse().unwrap_or(0.0);
-                    let y: f64 = tokens[i + 1].parse().unwrap_or(0.0);
-                    points.push(transform_point(x, y, transform));
-                    i += 2;
-                }
-            }
-            "Q" => {
-                // Quadratic Bezier curve
-                if i + 3 < tokens.len() {
-                    let cx: f64 = tokens[i].parse().unwrap_or(0.0);
-                    let cy: f64 = tokens[i + 1].parse().unwrap_or(0.0);
-                    let x: f64 = tokens[i + 2].parse().unwrap_or(0.0);
-                    let y: f64 = tokens[i + 3].parse().unwrap_or(0.0);
-
-                    let control = transform_point(cx, cy, transform);
-                    let end = transform_point(x, y, transform);
-
-                    // Add control point and end point for spline
-                    points.push(control);
-                    points.push(end);
-                    i += 4;
-                }
-            }
-            "Z" | "z" => {
-                // Close path - connect to first point
-                if !points.is_empty() {
-                    points.push(points[0]);
-                }
-                splines.push(points.clone());
-                points.clear();
-            }
-            _ => {
-                // Try to parse as number (implicit line continuation)
-                if let Ok(x) = cmd.parse::<f64>() {
-                    if i < tokens.len() {
-                        let y: f64 = tokens[i].parse().unwrap_or(0.0);
-                        points.push(transform_point(x, y, transform));
-                        i += 1;
-                    }
-                }
-            }
-        }
-    }
-
-    if !points.is_empty() {
-        splines.push(points);
-    }
-
-    splines
-}
-
-fn transform_point(x: f64, y: f64, m: &Matrix) -> Point {
+fn transform_point(p: Point, m: &Matrix) -> Point {
     Point {
-        x: m.a * x + m.c * y + m.e,
-        y: m.b * x + m.d * y + m.f,
+        x: m.a * p.x + m.c * p.y + m.e,
+        y: m.b * p.x + m.d * p.y + m.f,
     }
 }
 
-fn quadratic_bezier(p0: Point, p1: Point, p2: Point, t: f64) -> Point {
-    let t2 = 1.0 - t;
-    Point {
-        x: t2 * t2 * p0.x + 2.0 * t2 * t * p1.x + t * t * p2.x,
-        y: t2 * t2 * p0.y + 2.0 * t2 * t * p1.y + t * t * p2.y,
+/// Largest error on the contours when checking whether one is inside another,
+/// in mm
+const NESTING_TOLERANCE: f64 = 0.01;
+
+/// Degree of the splines
+const DEGREE: usize = 3;
+
+/// Knots and control points of a cubic B-spline following `contour` exactly
+///
+/// Every segment is turned into a cubic Bézier curve, and the curves are
+/// joined with knots of multiplicity 3: the spline goes through the end of
+/// every segment, the span between knots `i` and `i + 1` being segment `i`.
+/// The end knots have multiplicity 4 so the spline starts and ends on
+/// `contour.start`.
+fn bspline(contour: &Contour) -> (Vec<f64>, Vec<Point>) {
+    let n = contour.segments.len();
+    let mut points = Vec::with_capacity(DEGREE * n + 1);
+    points.push(contour.start);
+    for (start, segment) in contour.segments_from() {
+        points.extend(segment.to_cubic(start));
+    }
+    let mut knots = vec![0.0; DEGREE + 1];
+    for i in 1..n {
+        knots.extend([i as f64; DEGREE]);
     }
+    knots.extend([n as f64; DEGREE + 1]);
+    (knots, points)
 }
 
-fn write_spline(writer: &mut BufWriter<File>, points: &[Point]) -> IoResult<()> {
-    if points.is_empty() {
+fn write_spline(writer: &mut BufWriter<File>, layer: &str, contour: &Contour) -> IoResult<()> {
+    if contour.segments.is_empty() {
         return Ok(());
     }
 
+    let (knots, points) = bspline(contour);
     let n = points.len();
-    let degree = 3; // Cubic spline
-    let num_knots = n + degree + 1;
+    let degree = DEGREE;
+    let num_knots = knots.len();
 
     writeln!(writer, "0")?;
     writeln!(writer, "SPLINE")?;
     writeln!(writer, "8")?; // Layer
-    writeln!(writer, "0")?;
-    writeln!(writer, "70")?; // Spline flag (8 = closed spline)
+    writeln!(writer, "{}", layer)?;
+    writeln!(writer, "70")?; // Spline flag (8 = planar), closed by its points
     writeln!(writer, "8")?;
     writeln!(writer, "71")?; // Degree of spline
     writeln!(writer, "{}", degree)?;
@@ -300,10 +296,10 @@ fn write_spline(writer: &mut BufWriter<File>, points: &[Point]) -> IoResult<()>
     writeln!(writer, "74")?; // Number of fit points
     writeln!(writer, "0")?;
 
-    // Write knot values (uniform knot vector for closed spline)
-    for i in 0..num_knots {
+    // Write knot values
+    for knot in knots {
         writeln!(writer, "40")?;
-        writeln!(writer, "{}", i)?;
+        writeln!(writer, "{}", knot)?;
     }
 
     // Write control points
@@ -319,8 +315,40 @@ fn write_spline(writer: &mut BufWriter<File>, points: &[Point]) -> IoResult<()>
     Ok(())
 }
 
+/// Write a closed polygon as a lightweight polyline
+fn write_polyline(writer: &mut BufWriter<File>, layer: &str, points: &[Point]) -> IoResult<()> {
+    if points.len() < 2 {
+        return Ok(());
+    }
+
+    writeln!(writer, "0")?;
+    writeln!(writer, "LWPOLYLINE")?;
+    writeln!(writer, "8")?; // Layer
+    writeln!(writer, "{}", layer)?;
+    writeln!(writer, "90")?; // Number of vertices
+    writeln!(writer, "{}", points.len())?;
+    writeln!(writer, "70")?; // Polyline flag (1 = closed)
+    writeln!(writer, "1")?;
+
+    for point in points {
+        writeln!(writer, "10")?;
+        writeln!(writer, "{}", point.x)?;
+        writeln!(writer, "20")?;
+        writeln!(writer, "{}", point.y)?;
+    }
+
+    Ok(())
+}
+
 /// Write a path
-fn write_path(writer: &mut BufWriter<File>, height: f64, path: Path, scale: f64) -> IoResult<()> {
+fn write_path(
+    writer: &mut BufWriter<File>,
+    layer: &str,
+    height: f64,
+    path: Path,
+    scale: f64,
+    curves: Curves,
+) -> IoResult<()> {
     let transform = Matrix {
         a: scale,
         b: 0.0,
@@ -329,9 +357,23 @@ fn write_path(writer: &mut BufWriter<File>, height: f64, path: Path, scale: f64)
         e: path.x,
         f: height - path.y,
     };
-    let points_vec = parse_svg_path(path.d.as_str(), &transform);
-    for segment in points_vec {
-        write_spline(writer, &segment)?;
+    let contours = path::parse(path.d.as_str())
+        .iter()
+        .map(|contour| contour.map(|p| transform_point(p, &transform)))
+        .collect();
+    // Counters are cut before the outline around them, so that the letter
+    // does not move once free
+    let regions = path::regions(contours, NESTING_TOLERANCE);
+    for contour in regions
+        .iter()
+        .flat_map(|region| region.holes.iter().chain([&region.outer]))
+    {
+        match curves {
+            Curves::Splines => write_spline(writer, layer, contour)?,
+            Curves::Polylines { tolerance } => {
+                write_polyline(writer, layer, &contour.flatten(tolerance))?
+            }
+        }
     }
     Ok(())
 }
@@ -339,42 +381,67 @@ fn write_path(writer: &mut BufWriter<File>, height: f64, path: Path, scale: f64)
 /// Write a shape
 fn write_shape(
     writer: &mut BufWriter<File>,
+    layer: &str,
     height: f64,
     shape: Shape,
     scale: f64,
+    curves: Curves,
 ) -> IoResult<()> {
     match shape {
         Shape::Circle(c) => {
-            write_circle(writer, c)?;
+            write_circle(writer, layer, height, c)?;
         }
         Shape::Path(p) => {
-            write_path(writer, height, p, scale)?;
+            write_path(writer, layer, height, p, scale, curves)?;
+        }
+        Shape::Rect(r) => {
+            write_rectangle(
+                writer,
+                layer,
+                r.x,
+                height - r.y - r.height,
+                r.width,
+                r.height,
+            )?;
+        }
+        Shape::Polygon(p) => {
+            let points: Vec<Point> = p
+                .points
+                .iter()
+                .map(|p| Point::new(p.x, height - p.y))
+                .collect();
+            write_polyline(writer, layer, &points)?;
         }
     }
     Ok(())
 }
 
-pub fn generate(file: &PathBuf, cover: Cover) -> IoResult<()> {
+pub fn generate(file: &PathBuf, sheet: Sheet, curves: Curves) -> IoResult<()> {
     // Placeholder implementation for DXF generation
     println!("Generating DXF file at: {:?}", file);
     let mut writer = BufWriter::new(File::create(file)?);
 
-    let scale = cover.scale;
+    let scale = sheet.scale;
     println!("scale:{}", scale);
 
     // Define geometry parameters
-    let width = cover.width;
-    let height = cover.height;
+    let width = sheet.width;
+    let height = sheet.height;
 
     write_dxf_header(&mut writer, width, height)?;
-    write_dxf_tables(&mut writer)?;
+    let layers: Vec<(&str, u8)> = sheet
+        .layers
+        .iter()
+        .map(|l| (l.kind.name(), layer_color(l.kind)))
+        .collect();
+    write_dxf_tables(&mut writer, &layers)?;
     write_dxf_entities_start(&mut writer)?;
 
-    // Draw outer rectangle
-    write_rectangle(&mut writer, 0.0, 0.0, width, height)?;
-
-    for shape in cover.shapes {
-        write_shape(&mut writer, height, shape, scale)?;
+    for layer in sheet.layers {
+        let name = layer.kind.name();
+        for shape in layer.shapes {
+            write_shape(&mut writer, name, height, shape, scale, curves)?;
+        }
     }
 
     write_dxf_entities_end(&mut writer)?;
@@ -383,3 +450,271 @@ pub fn generate(file: &PathBuf, cover: Cover) -> IoResult<()> {
     writer.flush()?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::Segment;
+    use crate::sheet::{Layer, Rect};
+    use std::collections::BTreeSet;
+
+    /// Evaluate the B-spline at `u` with de Boor's algorithm
+    fn de_boor(knots: &[f64], points: &[Point], u: f64) -> Point {
+        let p = DEGREE;
+        // Last span starting at or before `u`, among the spans of the curve
+        let k = (p..points.len())
+            .rev()
+            .find(|i| knots[*i] <= u && knots[*i] < knots[*i + 1])
+            .unwrap();
+        let mut d: Vec<Point> = (0..=p).map(|j| points[j + k - p]).collect();
+        for r in 1..=p {
+            for j in (r..=p).rev() {
+                let i = j + k - p;
+                let alpha = (u - knots[i]) / (knots[i + 1 + p - r] - knots[i]);
+                d[j] = d[j - 1].lerp(d[j], alpha);
+            }
+        }
+        d[p]
+    }
+
+    #[test]
+    fn spline_follows_outline() {
+        let contour =
+            &path::parse("M 0 0 L 100 0 Q 200 0 200 100 C 200 180 150 250 60 200 Q 0 170 0 100 Z")
+                [0];
+        let (knots, points) = bspline(contour);
+        assert_eq!(knots.len(), points.len() + DEGREE + 1);
+        for (i, (start, segment)) in contour.segments_from().enumerate() {
+            for step in 0..=20 {
+                let t = step as f64 / 20.0;
+                let expected = segment.point_at(start, t);
+                let found = de_boor(&knots, &points, i as f64 + t);
+                assert!(
+                    (expected.x - found.x).abs() < 1e-9 && (expected.y - found.y).abs() < 1e-9,
+                    "segment {} at {}: {:?} != {:?}",
+                    i,
+                    t,
+                    expected,
+                    found
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn spline_is_closed() {
+        let contour = &path::parse("M 10 10 Q 20 30 30 10")[0];
+        assert_eq!(contour.segments.last(), Some(&Segment::Line(contour.start)));
+        let (knots, points) = bspline(contour);
+        let end = de_boor(&knots, &points, *knots.last().unwrap());
+        assert!((end.x - 10.0).abs() < 1e-9 && (end.y - 10.0).abs() < 1e-9);
+    }
+
+    /// Circle of radius `r` around the origin made of four cubic curves, as
+    /// in CFF fonts, counterclockwise when `ccw`
+    fn cubic_circle(r: f64, ccw: bool) -> String {
+        let k = 0.5523 * r;
+        let s = if ccw { 1.0 } else { -1.0 };
+        format!(
+            "M {r} 0 C {r} {a} {b} {c} 0 {c} C {nb} {c} {nr} {a} {nr} 0 \
+             C {nr} {na} {nb} {nc} 0 {nc} C {b} {nc} {r} {na} {r} 0 Z",
+            r = r,
+            nr = -r,
+            a = s * k,
+            na = -s * k,
+            b = k,
+            nb = -k,
+            c = s * r,
+            nc = -s * r,
+        )
+    }
+
+    #[test]
+    fn cubic_outlines() {
+        // CFF outlines wind the other way around than TrueType ones
+        let d = format!(
+            "{} {}",
+            cubic_circle(100.0, true),
+            cubic_circle(50.0, false)
+        );
+        for curves in [Curves::Splines, Curves::Polylines { tolerance: 0.01 }] {
+            let sheet = Sheet {
+                scale: 0.1,
+                width: 100.0,
+                height: 100.0,
+                layers: vec![Layer {
+                    kind: LayerKind::Letters,
+                    shapes: vec![Shape::Path(Path {
+                        d: d.clone(),
+                        x: 50.0,
+                        y: 50.0,
+                    })],
+                }],
+            };
+            let file = std::env::temp_dir().join("gen_front_cubic_outlines.dxf");
+            generate(&file, sheet, curves).unwrap();
+            let dxf = std::fs::read_to_string(&file).unwrap();
+            std::fs::remove_file(&file).unwrap();
+
+            let lines: Vec<&str> = dxf.lines().collect();
+            let entity = match curves {
+                Curves::Splines => "SPLINE",
+                Curves::Polylines { .. } => "LWPOLYLINE",
+            };
+            // Largest distance to the center of the points of every entity,
+            // the counter coming first
+            let mut radii: Vec<f64> = Vec::new();
+            let mut point = (0.0, 0.0);
+            for pair in lines.chunks(2) {
+                match (pair[0], pair[1]) {
+                    ("0", e) if e == entity => radii.push(0.0),
+                    ("10", x) => point.0 = x.parse::<f64>().unwrap() - 50.0,
+                    ("20", y) => {
+                        point.1 = y.parse::<f64>().unwrap() - 50.0;
+                        if let Some(r) = radii.last_mut() {
+                            *r = r.max(point.0.hypot(point.1));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            assert_eq!(radii.len(), 2, "{:?}", curves);
+            match curves {
+                // Control points of the curves stick out of the circle
+                Curves::Splines => assert!(radii[0] > 5.0 && radii[0] < 10.0 && radii[1] > 10.0),
+                Curves::Polylines { .. } => {
+                    assert!((radii[0] - 5.0).abs() < 0.01 && (radii[1] - 10.0).abs() < 0.01)
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn circles_are_flipped() {
+        // Hole near the top of a sheet taller than its content
+        let sheet = Sheet {
+            scale: 1.0,
+            width: 100.0,
+            height: 300.0,
+            layers: vec![Layer {
+                kind: LayerKind::Holes,
+                shapes: vec![Shape::Circle(Circle {
+                    cx: 10.0,
+                    cy: 20.0,
+                    r: 2.0,
+                })],
+            }],
+        };
+        let file = std::env::temp_dir().join("gen_front_circles_are_flipped.dxf");
+        generate(&file, sheet, Curves::Splines).unwrap();
+        let dxf = std::fs::read_to_string(&file).unwrap();
+        std::fs::remove_file(&file).unwrap();
+
+        let lines: Vec<&str> = dxf.lines().collect();
+        let circle = lines.iter().position(|l| *l == "CIRCLE").unwrap();
+        let center: Vec<(&str, &str)> = lines[circle + 3..]
+            .chunks(2)
+            .map(|pair| (pair[0], pair[1]))
+            .take_while(|pair| pair.0 != "0")
+            .filter(|pair| pair.0 == "10" || pair.0 == "20")
+            .collect();
+        assert_eq!(center, vec![("10", "10"), ("20", "280")]);
+    }
+
+    #[test]
+    fn shapes_on_named_layers() {
+        let sheet = Sheet {
+            scale: 1.0,
+            width: 100.0,
+            height: 100.0,
+            layers: vec![
+                Layer {
+                    kind: LayerKind::Outline,
+                    shapes: vec![Shape::Rect(Rect {
+                        x: 0.0,
+                        y: 0.0,
+                        width: 100.0,
+                        height: 100.0,
+                    })],
+                },
+                Layer {
+                    kind: LayerKind::Holes,
+                    shapes: vec![Shape::Circle(Circle {
+                        cx: 10.0,
+                        cy: 10.0,
+                        r: 2.0,
+                    })],
+                },
+                Layer {
+                    kind: LayerKind::Letters,
+                    shapes: vec![Shape::Path(Path {
+                        d: "M 0 0 L 10 0 L 10 10 Z".to_string(),
+                        x: 50.0,
+                        y: 50.0,
+                    })],
+                },
+            ],
+        };
+        let file = std::env::temp_dir().join("gen_front_named_layers.dxf");
+        generate(&file, sheet, Curves::Splines).unwrap();
+        let dxf = std::fs::read_to_string(&file).unwrap();
+        std::fs::remove_file(&file).unwrap();
+
+        let lines: Vec<&str> = dxf.lines().collect();
+        // Version reading splines and lightweight polylines
+        let header = lines.iter().position(|l| *l == "HEADER").unwrap();
+        assert_eq!(
+            lines[header + 1..header + 5],
+            ["9", "$ACADVER", "1", "AC1015"]
+        );
+        // Layer of every entity
+        let entities: Vec<(&str, &str)> = lines
+            .windows(4)
+            .filter(|w| w[0] == "0" && w[2] == "8")
+            .map(|w| (w[1], w[3]))
+            .collect();
+        assert_eq!(
+            entities,
+            vec![
+                ("LINE", "outline"),
+                ("LINE", "outline"),
+                ("LINE", "outline"),
+                ("LINE", "outline"),
+                ("CIRCLE", "holes"),
+                ("SPLINE", "letters"),
+            ]
+        );
+        // Declared layers with their color
+        let declared: Vec<(&str, &str)> = lines
+            .windows(8)
+            .filter(|w| w[0] == "0" && w[1] == "LAYER" && w[2] == "2")
+            .map(|w| (w[3], w[7]))
+            .collect();
+        assert_eq!(
+            declared,
+            vec![
+                ("0", "7"),
+                ("outline", "7"),
+                ("holes", "1"),
+                ("letters", "3")
+            ]
+        );
+        // Every layer gets its own cut settings
+        let colors: BTreeSet<u8> = [
+            LayerKind::Outline,
+            LayerKind::Holes,
+            LayerKind::Minutes,
+            LayerKind::Letters,
+            LayerKind::Leds,
+            LayerKind::Margins,
+            LayerKind::BoundingBoxes,
+            LayerKind::Baffles,
+            LayerKind::Cable,
+            LayerKind::Keyholes,
+        ]
+        .into_iter()
+        .map(layer_color)
+        .collect();
+        assert_eq!(colors.len(), 10);
+    }
+}
@@ -0,0 +1,245 @@
+//! Enclosure: four sides and a back, held together by finger joints, the
+//! cover closing the front.
+
+use crate::back;
+use crate::cover::Sizes;
+use crate::design::Design;
+use crate::path::Point;
+use crate::sheet::{LayerKind, Polygon, Shape, Sheet};
+
+/// Edge of a panel, cut into `count` fingers
+///
+/// Fingers alternate between tabs, reaching the edge, and notches, one
+/// thickness deep. Two edges joining each other have the same count and
+/// opposite `tab_first`.
+#[derive(Debug, Clone, Copy)]
+struct Edge {
+    count: usize,
+    tab_first: bool,
+}
+
+impl Edge {
+    /// Straight edge, without any finger
+    const PLAIN: Edge = Edge {
+        count: 1,
+        tab_first: true,
+    };
+
+    /// Edge of `length` with fingers about `finger` wide
+    ///
+    /// The count is odd so that both ends are alike, and the joint the same
+    /// whichever way the panels are turned.
+    fn fingers(length: f64, finger: f64, tab_first: bool) -> Edge {
+        let pairs = ((length / finger - 1.0) / 2.0).round().max(0.0) as usize;
+        Edge {
+            count: 2 * pairs + 1,
+            tab_first,
+        }
+    }
+
+    /// How far finger `k` is from the edge
+    fn depth(self, k: usize, thickness: f64) -> f64 {
+        if (k % 2 == 0) == self.tab_first {
+            0.0
+        } else {
+            thickness
+        }
+    }
+}
+
+/// Panel of `width` by `height`, its top left corner at `origin`
+///
+/// `edges` are the top, right, bottom and left edges. Each is followed
+/// clockwise as seen on the sheet, so the corners are notched when either
+/// edge meeting there is.
+fn panel(origin: Point, width: f64, height: f64, edges: [Edge; 4], thickness: f64) -> Shape {
+    let corners = [(0.0, 0.0), (width, 0.0), (width, height), (0.0, height)];
+    let directions = [(1.0, 0.0), (0.0, 1.0), (-1.0, 0.0), (0.0, -1.0)];
+    let mut points: Vec<Point> = Vec::new();
+    for (i, edge) in edges.iter().enumerate() {
+        let previous = edges[(i + 3) % 4];
+        let next = edges[(i + 1) % 4];
+        let length = if i % 2 == 0 { width } else { height };
+        let (cx, cy) = corners[i];
+        let (ux, uy) = directions[i];
+        // Inward normal
+        let (nx, ny) = (-uy, ux);
+        for k in 0..edge.count {
+            let depth = edge.depth(k, thickness);
+            let from = if k == 0 {
+                previous.depth(previous.count - 1, thickness)
+            } else {
+                length * k as f64 / edge.count as f64
+            };
+            let to = if k == edge.count - 1 {
+                length - next.depth(0, thickness)
+            } else {
+                length * (k + 1) as f64 / edge.count as f64
+            };
+            for s in [from, to] {
+                let p = Point::new(
+                    origin.x + cx + ux * s + nx * depth,
+                    origin.y + cy + uy * s + ny * depth,
+                );
+                let last = points.last().copied();
+                if last.map_or(true, |l| (l.x - p.x).abs() + (l.y - p.y).abs() > 1e-9) {
+                    points.push(p);
+                }
+            }
+        }
+    }
+    if (points[0].x - points[points.len() - 1].x).abs()
+        + (points[0].y - points[points.len() - 1].y).abs()
+        < 1e-9
+    {
+        points.pop();
+    }
+    Shape::Polygon(Polygon { points })
+}
+
+/// Generate the back and the four sides, laid out one under the other
+///
+/// The back is the back plate, with fingers on its edges: it takes the whole
+/// width and height of the cover, the sides fitting around it and into each
+/// other. The front edge of the sides is straight, the cover resting on it.
+/// The back owns the corners of the box, the top and bottom sides own the
+/// edges along the depth.
+pub fn generate(sizes: &Sizes, design: &Design) -> Sheet {
+    let enclosure = &design.enclosure;
+    let t = enclosure.thickness;
+    let finger = enclosure.finger;
+    let depth = enclosure.depth;
+    let gap = enclosure.gap;
+    let (width, height) = (sizes.width, sizes.height);
+
+    let mut sheet = back::generate(sizes, design);
+    let outline = sheet
+        .layers
+        .iter_mut()
+        .find(|l| l.kind == LayerKind::Outline)
+        .unwrap();
+    let horizontal = Edge::fingers(width, finger, true);
+    let vertical = Edge::fingers(height, finger, true);
+    outline.shapes = vec![panel(
+        Point::new(0.0, 0.0),
+        width,
+        height,
+        [horizontal, vertical, horizontal, vertical],
+        t,
+    )];
+
+    // Sides, their back edge on top and their front edge at the bottom
+    let mut y = height + gap;
+    for (length, owns_edges) in [
+        (width, true),
+        (width, true),
+        (height, false),
+        (height, false),
+    ] {
+        let back_edge = Edge::fingers(length, finger, false);
+        let depth_edge = Edge::fingers(depth, finger, owns_edges);
+        outline.shapes.push(panel(
+            Point::new(0.0, y),
+            length,
+            depth,
+            [back_edge, depth_edge, Edge::PLAIN, depth_edge],
+            t,
+        ));
+        y += depth + gap;
+    }
+    println!(
+        "Enclosure: back of {}x{}mm, sides of {}x{}mm and {}x{}mm",
+        width, height, width, depth, height, depth
+    );
+
+    sheet.width = width.max(height);
+    sheet.height = y - gap;
+    sheet
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::sizes;
+    use crate::path::Contour;
+
+    #[test]
+    fn joined_edges_interlock() {
+        let t = 3.0;
+        for length in [30.0, 95.0, 250.0] {
+            let a = Edge::fingers(length, 10.0, true);
+            let b = Edge::fingers(length, 10.0, false);
+            assert_eq!(a.count % 2, 1);
+            assert_eq!(a.count, b.count);
+            for k in 0..a.count {
+                assert_eq!(a.depth(k, t) + b.depth(k, t), t);
+            }
+        }
+    }
+
+    #[test]
+    fn panel_notches() {
+        // 3 fingers on every edge, the middle one notched
+        let edge = Edge {
+            count: 3,
+            tab_first: true,
+        };
+        let Shape::Polygon(p) = panel(Point::new(0.0, 0.0), 30.0, 30.0, [edge; 4], 1.0) else {
+            unreachable!()
+        };
+        assert_eq!(p.points.len(), 20);
+        // Clockwise on the sheet, so positive with its y axis going down
+        assert!((Contour::polygon(&p.points).signed_area() - (900.0 - 4.0 * 10.0)).abs() < 1e-9);
+
+        // Notched corners, the plain bottom edge following the notches of the
+        // sides
+        let edge = Edge {
+            count: 3,
+            tab_first: false,
+        };
+        let Shape::Polygon(p) = panel(
+            Point::new(5.0, 5.0),
+            30.0,
+            30.0,
+            [edge, edge, Edge::PLAIN, edge],
+            1.0,
+        ) else {
+            unreachable!()
+        };
+        assert_eq!(p.points[0], Point::new(6.0, 6.0));
+        assert!(
+            (Contour::polygon(&p.points).signed_area() - (900.0 - 3.0 * 20.0 + 2.0)).abs() < 1e-9
+        );
+    }
+
+    #[test]
+    fn box_is_laid_out() {
+        let design = Design::default();
+        let sizes = Sizes {
+            height: 200.0,
+            ..sizes()
+        };
+        let sheet = generate(&sizes, &design);
+        let outline = sheet
+            .layers
+            .iter()
+            .find(|l| l.kind == LayerKind::Outline)
+            .unwrap();
+        assert_eq!(outline.shapes.len(), 5);
+        // The back keeps the holes of the back plate
+        assert!(sheet.layers.iter().any(|l| l.kind == LayerKind::Holes));
+        let depth = design.enclosure.depth;
+        let gap = design.enclosure.gap;
+        assert_eq!(sheet.width, 250.0);
+        assert_eq!(sheet.height, 200.0 + 4.0 * (depth + gap));
+        for shape in &outline.shapes {
+            let Shape::Polygon(p) = shape else {
+                panic!("panels must be polygons");
+            };
+            assert!(p
+                .points
+                .iter()
+                .all(|p| p.x >= 0.0 && p.x <= sheet.width && p.y >= 0.0 && p.y <= sheet.height));
+        }
+    }
+}
@@ -0,0 +1,203 @@
+//! Test fixtures: cover sizes, and minimal TrueType fonts built in memory.
+
+use crate::cover::Sizes;
+use ttf_parser::Tag;
+
+/// Sizes of a 250mm square cover
+pub fn sizes() -> Sizes {
+    Sizes {
+        width: 250.0,
+        height: 250.0,
+        scale: 0.01,
+        led_spacing: 17.0,
+        glyph_height: 14.0,
+        row_spacing: 21.0,
+    }
+}
+
+/// Polygon outline, in font units
+pub type Outline = Vec<(i16, i16)>;
+
+/// Square outline of `size` with its bottom left corner at `x`, `y`,
+/// counterclockwise
+pub fn square(x: i16, y: i16, size: i16) -> Outline {
+    vec![(x, y), (x + size, y), (x + size, y + size), (x, y + size)]
+}
+
+/// TrueType font with straight outlines only
+#[derive(Clone)]
+pub struct TestFont {
+    pub units_per_em: u16,
+    /// Cap height of the OS/2 table, absent when `None`
+    pub cap_height: Option<i16>,
+    /// Character and contours of every glyph
+    pub glyphs: Vec<(char, Vec<Outline>)>,
+    /// Tag, minimum, default and maximum of every variation axis
+    pub axes: Vec<(Tag, f32, f32, f32)>,
+}
+
+impl TestFont {
+    /// Font of 1000 units per em with a `size` square glyph for every
+    /// character of `chars`
+    pub fn squares(chars: &str, size: i16) -> TestFont {
+        TestFont {
+            units_per_em: 1000,
+            cap_height: Some(size),
+            glyphs: chars
+                .chars()
+                .map(|c| (c, vec![square(0, 0, size)]))
+                .collect(),
+            axes: Vec::new(),
+        }
+    }
+
+    /// Font file, its table offsets counted from `base`
+    fn build_at(&self, base: usize) -> Vec<u8> {
+        let mut glyf: Vec<u8> = Vec::new();
+        // No outline for .notdef
+        let mut loca: Vec<u8> = vec![0; 8];
+        for (_, contours) in &self.glyphs {
+            if contours.is_empty() {
+                // Blank glyph, without any data
+                loca.extend((glyf.len() as u32).to_be_bytes());
+                continue;
+            }
+            let points: Vec<(i16, i16)> = contours.iter().flatten().copied().collect();
+            let xs = points.iter().map(|p| p.0);
+            let ys = points.iter().map(|p| p.1);
+            glyf.extend(i16_bytes(&[
+                contours.len() as i16,
+                xs.clone().min().unwrap(),
+                ys.clone().min().unwrap(),
+                xs.max().unwrap(),
+                ys.max().unwrap(),
+            ]));
+            let mut end = 0;
+            for contour in contours {
+                end += contour.len();
+                glyf.extend(((end - 1) as u16).to_be_bytes());
+            }
+            // No instructions, every point on the curve with 16-bit deltas
+            glyf.extend([0, 0]);
+            glyf.extend(vec![1; points.len()]);
+            for coordinate in [|p: (i16, i16)| p.0, |p: (i16, i16)| p.1] {
+                let mut last = 0;
+                for p in &points {
+                    glyf.extend((coordinate(*p) - last).to_be_bytes());
+                    last = coordinate(*p);
+                }
+            }
+            loca.extend((glyf.len() as u32).to_be_bytes());
+        }
+        let count = self.glyphs.len() as u16 + 1;
+
+        let mut head: Vec<u8> = Vec::new();
+        head.extend(0x0001_0000u32.to_be_bytes());
+        head.extend([0; 8]);
+        head.extend(0x5F0F_3CF5u32.to_be_bytes());
+        head.extend([0; 2]);
+        head.extend(self.units_per_em.to_be_bytes());
+        head.extend([0; 16]);
+        head.extend(i16_bytes(&[0, -200, 1000, 1000]));
+        // Style, smallest size, direction, long offsets
+        head.extend(i16_bytes(&[0, 0, 2, 1, 0]));
+
+        let mut hhea: Vec<u8> = Vec::new();
+        hhea.extend(0x0001_0000u32.to_be_bytes());
+        hhea.extend(i16_bytes(&[800, -200, 0]));
+        hhea.extend([0; 26]);
+
+        let mut maxp: Vec<u8> = Vec::new();
+        maxp.extend(0x0000_5000u32.to_be_bytes());
+        maxp.extend(count.to_be_bytes());
+
+        // Windows full Unicode subtable, one glyph per group
+        let mut cmap: Vec<u8> = Vec::new();
+        cmap.extend(i16_bytes(&[0, 1, 3, 10]));
+        cmap.extend(12u32.to_be_bytes());
+        cmap.extend(i16_bytes(&[12, 0]));
+        cmap.extend((16 + 12 * self.glyphs.len() as u32).to_be_bytes());
+        cmap.extend(0u32.to_be_bytes());
+        cmap.extend((self.glyphs.len() as u32).to_be_bytes());
+        for (id, (c, _)) in self.glyphs.iter().enumerate() {
+            for value in [*c as u32, *c as u32, id as u32 + 1] {
+                cmap.extend(value.to_be_bytes());
+            }
+        }
+
+        let mut tables: Vec<(&[u8; 4], Vec<u8>)> = vec![
+            (b"cmap", cmap),
+            (b"glyf", glyf),
+            (b"head", head),
+            (b"hhea", hhea),
+            (b"loca", loca),
+            (b"maxp", maxp),
+        ];
+        if let Some(cap_height) = self.cap_height {
+            // Version 2, all zeros up to the cap height
+            let mut os2: Vec<u8> = vec![0; 96];
+            os2[..2].copy_from_slice(&2u16.to_be_bytes());
+            os2[88..90].copy_from_slice(&cap_height.to_be_bytes());
+            tables.push((b"OS/2", os2));
+        }
+        if !self.axes.is_empty() {
+            let mut fvar: Vec<u8> = Vec::new();
+            fvar.extend(0x0001_0000u32.to_be_bytes());
+            let axes = self.axes.len() as i16;
+            fvar.extend(i16_bytes(&[16, 2, axes, 20, 0, 4 + 4 * axes]));
+            for (tag, min, default, max) in &self.axes {
+                fvar.extend(tag.to_bytes());
+                for value in [min, default, max] {
+                    fvar.extend(((value * 65536.0) as i32).to_be_bytes());
+                }
+                fvar.extend([0; 4]);
+            }
+            tables.push((b"fvar", fvar));
+        }
+        tables.sort_by_key(|(tag, _)| **tag);
+
+        let mut font: Vec<u8> = Vec::new();
+        font.extend(0x0001_0000u32.to_be_bytes());
+        font.extend(i16_bytes(&[tables.len() as i16, 0, 0, 0]));
+        let mut offset = base + 12 + 16 * tables.len();
+        for (tag, data) in &tables {
+            font.extend(*tag);
+            font.extend([0; 4]);
+            font.extend((offset as u32).to_be_bytes());
+            font.extend((data.len() as u32).to_be_bytes());
+            offset += data.len();
+        }
+        for (_, data) in tables {
+            font.extend(data);
+        }
+        font
+    }
+
+    /// Font file
+    pub fn build(&self) -> Vec<u8> {
+        self.build_at(0)
+    }
+}
+
+/// Font collection file made of `fonts`
+pub fn collection(fonts: &[TestFont]) -> Vec<u8> {
+    let mut data: Vec<u8> = Vec::new();
+    data.extend(b"ttcf");
+    data.extend(0x0001_0000u32.to_be_bytes());
+    data.extend((fonts.len() as u32).to_be_bytes());
+    let mut offset = 12 + 4 * fonts.len();
+    let mut faces: Vec<u8> = Vec::new();
+    for font in fonts {
+        data.extend((offset as u32).to_be_bytes());
+        let face = font.build_at(offset);
+        offset += face.len();
+        faces.extend(face);
+    }
+    data.extend(faces);
+    data
+}
+
+/// Big-endian bytes of every value
+fn i16_bytes(values: &[i16]) -> Vec<u8> {
+    values.iter().flat_map(|v| v.to_be_bytes()).collect()
+}
@@ -1,8 +1,8 @@
 //! Font analysis module.
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use thiserror::Error;
-use ttf_parser::{Face, GlyphId, OutlineBuilder, Rect};
+use ttf_parser::{Face, GlyphId, OutlineBuilder, Rect, Tag};
 
 pub const FLOWER: char = '\u{2698}';
 
@@ -10,6 +10,53 @@ pub const FLOWER: char = '\u{2698}';
 pub enum Error {
     #[error("Font Face Parse Error: {0}")]
     FaceParse(#[from] ttf_parser::FaceParsingError),
+    #[error("Fallback font {index}: {source}")]
+    Fallback {
+        index: usize,
+        source: ttf_parser::FaceParsingError,
+    },
+    #[error("Font has no glyph for {0:?}")]
+    MissingGlyph(char),
+    #[error("Font has no glyph for {}", list(.0))]
+    MissingGlyphs(Vec<char>),
+    #[error("No letter of the grid has an outline to measure")]
+    NoOutline,
+    #[error("Font file has {count} face(s), no face {index}")]
+    FaceIndex { index: u32, count: u32 },
+    #[error("Font has no {0} variation axis")]
+    MissingAxis(Tag),
+    #[error("Font {tag} axis goes from {min} to {max}, {value} is out of range")]
+    AxisRange {
+        tag: Tag,
+        min: f32,
+        max: f32,
+        value: f32,
+    },
+}
+
+/// Characters separated by commas, quoted
+fn list(chars: &[char]) -> String {
+    chars
+        .iter()
+        .map(|c| format!("{:?}", c))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// Weight variation axis
+pub const WEIGHT: Tag = Tag::from_bytes(b"wght");
+/// Width variation axis
+pub const WIDTH: Tag = Tag::from_bytes(b"wdth");
+/// Optical size variation axis
+pub const OPTICAL_SIZE: Tag = Tag::from_bytes(b"opsz");
+
+/// Face to use in a font file, and its position on the variation axes
+#[derive(Debug, Clone, Default)]
+pub struct Instance {
+    /// Index of the face in a collection, 0 for a single font
+    pub face: u32,
+    /// Value of every variation axis to set, the others keep their default
+    pub axes: Vec<(Tag, f32)>,
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -38,13 +85,18 @@ pub struct FontAnalysis {
     pub ascender: i16,
     /// Maximum height of the font
     pub y_max: i16,
+    /// Height of the capital letters, `y_max` when unknown
+    pub cap_height: i16,
+    /// Height of the lowercase letters, `cap_height` when unknown
+    pub x_height: i16,
     /// Average glyph width
     pub glyph_width_avg: f64,
     /// HashMap of glyphs
     pub glyphs: HashMap<char, Glyph>,
 }
 
-struct Builder<'a>(&'a mut String);
+/// Writes outlines as path data, scaled by its second field
+struct Builder<'a>(&'a mut String, f32);
 
 impl Builder<'_> {
     fn finish(&mut self) {
@@ -57,31 +109,47 @@ impl Builder<'_> {
 impl OutlineBuilder for Builder<'_> {
     fn move_to(&mut self, x: f32, y: f32) {
         use std::fmt::Write;
-        write!(self.0, "M {} {} ", x, y).unwrap()
+        let s = self.1;
+        write!(self.0, "M {} {} ", x * s, y * s).unwrap()
     }
 
     fn line_to(&mut self, x: f32, y: f32) {
         use std::fmt::Write;
-        write!(self.0, "L {} {} ", x, y).unwrap()
+        let s = self.1;
+        write!(self.0, "L {} {} ", x * s, y * s).unwrap()
     }
 
     fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
         use std::fmt::Write;
-        write!(self.0, "Q {} {} {} {} ", x1, y1, x, y).unwrap()
+        let s = self.1;
+        write!(self.0, "Q {} {} {} {} ", x1 * s, y1 * s, x * s, y * s).unwrap()
     }
 
     fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
         use std::fmt::Write;
-        write!(self.0, "C {} {} {} {} {} {} ", x1, y1, x2, y2, x, y).unwrap()
+        let s = self.1;
+        write!(
+            self.0,
+            "C {} {} {} {} {} {} ",
+            x1 * s,
+            y1 * s,
+            x2 * s,
+            y2 * s,
+            x * s,
+            y * s
+        )
+        .unwrap()
     }
 
     fn close(&mut self) {
         self.0.push_str("Z ")
     }
 }
-fn generate_path(face: &Face, glyph_id: GlyphId) -> (String, BoundingBox) {
+
+/// Outline of a glyph and its bounding box, scaled by `scale`
+fn generate_path(face: &Face, glyph_id: GlyphId, scale: f32) -> (String, BoundingBox) {
     let mut path_buf = String::new();
-    let mut builder = Builder(&mut path_buf);
+    let mut builder = Builder(&mut path_buf, scale);
     let bbox = match face.outline_glyph(glyph_id, &mut builder) {
         Some(v) => v,
         None => Rect {
@@ -92,19 +160,91 @@ fn generate_path(face: &Face, glyph_id: GlyphId) -> (String, BoundingBox) {
         },
     };
     builder.finish();
+    let scaled = |v: i16| (f32::from(v) * scale).round() as i16;
     let custom_bbox = BoundingBox {
-        x_min: bbox.x_min,
-        y_min: bbox.y_min,
-        x_max: bbox.x_max,
-        y_max: bbox.y_max,
+        x_min: scaled(bbox.x_min),
+        y_min: scaled(bbox.y_min),
+        x_max: scaled(bbox.x_max),
+        y_max: scaled(bbox.y_max),
     };
     (path_buf, custom_bbox)
 }
 
+/// Height of the capital letters, from the OS/2 table or the H glyph
+fn cap_height(face: &Face) -> Option<f32> {
+    face.capital_height()
+        .filter(|h| *h > 0)
+        .or_else(|| {
+            let h = face.glyph_index('H')?;
+            Some(face.glyph_bounding_box(h)?.y_max)
+        })
+        .map(f32::from)
+}
+
+/// Height of the lowercase letters, from the OS/2 table or the x glyph
+fn x_height(face: &Face) -> Option<f32> {
+    face.x_height()
+        .filter(|h| *h > 0)
+        .or_else(|| {
+            let x = face.glyph_index('x')?;
+            Some(face.glyph_bounding_box(x)?.y_max)
+        })
+        .map(f32::from)
+}
+
+/// Scale bringing the glyphs of `fallback` to the size of the ones of
+/// `primary`: same cap height, or same em size when either has none
+fn fallback_scale(primary: &Face, fallback: &Face) -> f32 {
+    match (cap_height(primary), cap_height(fallback)) {
+        (Some(p), Some(f)) => p / f,
+        _ => f32::from(primary.units_per_em()) / f32::from(fallback.units_per_em()),
+    }
+}
+
 impl FontAnalysis {
-    /// Create a new font analysis from a TTF file
-    pub fn analyze(font: Vec<u8>) -> Result<Self, Error> {
-        let face = Face::parse(&font, 0)?;
+    /// Create a new font analysis from a TTF, OTF or TTC file, loading the
+    /// `letters` of the grid and the `marker` glyph used for the minutes, if
+    /// any, from `instance`
+    ///
+    /// Characters missing from the font are looked up in the `fallbacks`
+    /// files, in order, and scaled to the cap height of the font. Fallbacks
+    /// are read at their first face, on the default of their variation axes,
+    /// whatever the `instance`. The size of
+    /// the letters is measured on the `letters` only. Fails with every
+    /// character missing from all the fonts.
+    pub fn analyze(
+        font: Vec<u8>,
+        fallbacks: &[Vec<u8>],
+        letters: &BTreeSet<char>,
+        marker: Option<char>,
+        instance: &Instance,
+    ) -> Result<Self, Error> {
+        let count = ttf_parser::fonts_in_collection(&font).unwrap_or(1);
+        if instance.face >= count {
+            return Err(Error::FaceIndex {
+                index: instance.face,
+                count,
+            });
+        }
+        let mut face = Face::parse(&font, instance.face)?;
+        for (tag, value) in &instance.axes {
+            let axis = face
+                .variation_axes()
+                .into_iter()
+                .find(|a| a.tag == *tag)
+                .ok_or(Error::MissingAxis(*tag))?;
+            if *value < axis.min_value || *value > axis.max_value {
+                return Err(Error::AxisRange {
+                    tag: *tag,
+                    min: axis.min_value,
+                    max: axis.max_value,
+                    value: *value,
+                });
+            }
+            face.set_variation(*tag, *value)
+                .ok_or(Error::MissingAxis(*tag))?;
+        }
+        println!("Face: {} of {}", instance.face, count);
         let units_per_em = face.units_per_em();
         println!("Units per EM: {:?}", units_per_em);
         println!("Ascender: {}", face.ascender());
@@ -118,43 +258,288 @@ impl FontAnalysis {
         println!("Weight: {:?}", face.weight());
         println!("Width: {:?}", face.width());
         println!("Variable: {:?}", face.is_variable());
+        for axis in face.variation_axes() {
+            println!(
+                "Axis {}: {} to {}, default {}",
+                axis.tag, axis.min_value, axis.max_value, axis.def_value
+            );
+        }
+        let mut faces = vec![(face, 1.0)];
+        for (index, data) in fallbacks.iter().enumerate() {
+            let fallback =
+                Face::parse(data, 0).map_err(|source| Error::Fallback { index, source })?;
+            let scale = fallback_scale(&faces[0].0, &fallback);
+            println!("Fallback font {}: scaled by {}", index, scale);
+            faces.push((fallback, scale));
+        }
+        // Index of the first face with a glyph for `c`
+        let lookup = |c: char| faces.iter().position(|(f, _)| f.glyph_index(c).is_some());
+
+        let missing: Vec<char> = letters
+            .iter()
+            .chain(&marker)
+            .copied()
+            .filter(|c| lookup(*c).is_none())
+            .collect();
+        if !missing.is_empty() {
+            return Err(Error::MissingGlyphs(missing));
+        }
         let mut glyphs = HashMap::new();
         let mut y_max = i16::MIN;
         let mut glyphs_count = 0;
-        let mut glyph_width_sum = 0;
-        for c in ('A'..='Z').chain(vec!['-']) {
-            if let Some(glyph_id) = face.glyph_index(c) {
-                let (path, bb) = generate_path(&face, glyph_id);
+        let mut glyph_width_sum: i32 = 0;
+        for c in letters.iter().chain(&marker) {
+            let index = lookup(*c).ok_or(Error::MissingGlyph(*c))?;
+            let (face, scale) = &faces[index];
+            let glyph_id = face.glyph_index(*c).ok_or(Error::MissingGlyph(*c))?;
+            let (path, bb) = generate_path(face, glyph_id, *scale);
+            if index == 0 {
                 println!("Glyph {:?} bounding box: {:?}", c, bb);
+            } else {
+                println!(
+                    "Glyph {:?} bounding box: {:?}, from fallback font {}",
+                    c,
+                    bb,
+                    index - 1
+                );
+            }
+            // Blank glyphs have no size to measure
+            if letters.contains(c) && !path.is_empty() {
                 if bb.y_max > y_max {
                     y_max = bb.y_max;
                 }
                 glyphs_count += 1;
-                glyph_width_sum += bb.x_max - bb.x_min;
-                let glyph = Glyph { path, bbox: bb };
-                glyphs.insert(c, glyph);
+                glyph_width_sum += i32::from(bb.x_max - bb.x_min);
             }
-        }
-        if let Some(glyph_id) = face.glyph_index(FLOWER) {
-            let (path, bb) = generate_path(&face, glyph_id);
-            println!("Glyph {:?} bounding box: {:?}", ' ', bb);
             let glyph = Glyph { path, bbox: bb };
-            glyphs.insert(FLOWER, glyph);
+            glyphs.insert(*c, glyph);
         }
+        if glyphs_count == 0 {
+            return Err(Error::NoOutline);
+        }
+        let face = &faces[0].0;
         let descender = face.descender();
         let ascender = face.ascender();
+        let cap_height = cap_height(face).map_or(y_max, |h| h.round() as i16);
+        let x_height = x_height(face).map_or(cap_height, |h| h.round() as i16);
+        println!("Cap height: {}", cap_height);
         Ok(Self {
             descender,
             ascender,
             y_max,
+            cap_height,
+            x_height,
             glyph_width_avg: glyph_width_sum as f64 / glyphs_count as f64,
             glyphs,
         })
     }
+
+    /// Glyph of `c`
+    pub fn glyph(&self, c: char) -> Result<&Glyph, Error> {
+        self.glyphs.get(&c).ok_or(Error::MissingGlyph(c))
+    }
 }
 
-/// Analyze a font file
-pub fn analyze_font(font: Vec<u8>) -> Result<FontAnalysis, Error> {
-    let analysis = FontAnalysis::analyze(font)?;
+/// Analyze a font file and its fallbacks
+pub fn analyze_font(
+    font: Vec<u8>,
+    fallbacks: &[Vec<u8>],
+    letters: &BTreeSet<char>,
+    marker: Option<char>,
+    instance: &Instance,
+) -> Result<FontAnalysis, Error> {
+    let analysis = FontAnalysis::analyze(font, fallbacks, letters, marker, instance)?;
     Ok(analysis)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dxf::{self, Curves};
+    use crate::fixtures::{collection, square, TestFont};
+    use crate::path;
+    use crate::sheet::{Layer, LayerKind, Path, Shape, Sheet};
+
+    fn analyze(font: Vec<u8>, letters: &str, instance: &Instance) -> Result<FontAnalysis, Error> {
+        FontAnalysis::analyze(font, &[], &letters.chars().collect(), None, instance)
+    }
+
+    #[test]
+    fn outlines_are_scaled() {
+        let mut d = String::new();
+        let mut builder = Builder(&mut d, 0.5);
+        builder.move_to(0.0, 0.0);
+        builder.line_to(100.0, 0.0);
+        builder.quad_to(100.0, 50.0, 50.0, 50.0);
+        builder.curve_to(30.0, 50.0, 10.0, 30.0, 0.0, 20.0);
+        builder.close();
+        builder.finish();
+        assert_eq!(d, "M 0 0 L 50 0 Q 50 25 25 25 C 15 25 5 15 0 10 Z");
+    }
+
+    #[test]
+    fn every_missing_glyph_is_named() {
+        let error = Error::MissingGlyphs(vec!['É', 'Ñ', '\'']);
+        assert_eq!(error.to_string(), "Font has no glyph for 'É', 'Ñ', '\\''");
+    }
+
+    #[test]
+    fn every_missing_character_is_collected() {
+        let font = TestFont::squares("ABC", 700).build();
+        let fallback = TestFont::squares("D", 700).build();
+        let error = FontAnalysis::analyze(
+            font,
+            &[fallback],
+            &"ABDÉÑQ".chars().collect(),
+            Some(FLOWER),
+            &Instance::default(),
+        )
+        .unwrap_err();
+        match error {
+            Error::MissingGlyphs(missing) => assert_eq!(missing, vec!['Q', 'É', 'Ñ', FLOWER]),
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn face_of_a_collection() {
+        let file = collection(&[TestFont::squares("A", 500), TestFont::squares("A", 700)]);
+        let instance = Instance {
+            face: 1,
+            ..Instance::default()
+        };
+        let font = analyze(file.clone(), "A", &instance).unwrap();
+        assert_eq!(font.y_max, 700);
+        let instance = Instance {
+            face: 2,
+            ..Instance::default()
+        };
+        assert!(matches!(
+            analyze(file, "A", &instance),
+            Err(Error::FaceIndex { index: 2, count: 2 })
+        ));
+        // A single font has a single face
+        assert!(matches!(
+            analyze(TestFont::squares("A", 500).build(), "A", &instance),
+            Err(Error::FaceIndex { index: 2, count: 1 })
+        ));
+    }
+
+    #[test]
+    fn variation_axes() {
+        let font = TestFont {
+            axes: vec![(WEIGHT, 100.0, 400.0, 900.0)],
+            ..TestFont::squares("A", 500)
+        }
+        .build();
+        let instance = |axes: Vec<(Tag, f32)>| Instance { face: 0, axes };
+        assert!(analyze(font.clone(), "A", &instance(vec![(WEIGHT, 700.0)])).is_ok());
+        assert!(matches!(
+            analyze(font.clone(), "A", &instance(vec![(WIDTH, 75.0)])),
+            Err(Error::MissingAxis(WIDTH))
+        ));
+        match analyze(font, "A", &instance(vec![(WEIGHT, 1000.0)])) {
+            Err(Error::AxisRange {
+                tag,
+                min,
+                max,
+                value,
+            }) => assert_eq!((tag, min, max, value), (WEIGHT, 100.0, 900.0, 1000.0)),
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn overlapping_outlines_are_rendered() {
+        // Two squares, each starting inside the other, as variable fonts keep
+        // them
+        let font = TestFont {
+            glyphs: vec![(
+                'X',
+                vec![
+                    vec![(500, 250), (500, 500), (0, 500), (0, 0), (500, 0)],
+                    square(150, 50, 500),
+                ],
+            )],
+            ..TestFont::squares("", 500)
+        }
+        .build();
+        let font = analyze(font, "X", &Instance::default()).unwrap();
+        let d = font.glyph('X').unwrap().path.clone();
+        assert_eq!(path::regions(path::parse(&d), 1.0).len(), 2);
+        let sheet = Sheet {
+            scale: 0.01,
+            width: 20.0,
+            height: 20.0,
+            layers: vec![Layer {
+                kind: LayerKind::Letters,
+                shapes: vec![Shape::Path(Path { d, x: 5.0, y: 15.0 })],
+            }],
+        };
+        let file = std::env::temp_dir().join("gen_front_overlapping_outlines.dxf");
+        dxf::generate(&file, sheet, Curves::Splines).unwrap();
+        std::fs::remove_file(&file).unwrap();
+    }
+
+    #[test]
+    fn fallbacks_in_order() {
+        let primary = TestFont::squares("A", 700).build();
+        // B is in both fallbacks, C in the second only
+        let first = TestFont::squares("B", 350).build();
+        let second = TestFont {
+            glyphs: vec![
+                ('B', vec![vec![(0, 0), (100, 0), (100, 200), (0, 200)]]),
+                ('C', vec![square(0, 0, 100)]),
+            ],
+            ..TestFont::squares("", 100)
+        }
+        .build();
+        let font = FontAnalysis::analyze(
+            primary,
+            &[first, second],
+            &"ABC".chars().collect(),
+            None,
+            &Instance::default(),
+        )
+        .unwrap();
+        for c in ['A', 'B', 'C'] {
+            let bbox = font.glyph(c).unwrap().bbox;
+            assert_eq!((bbox.x_max, bbox.y_max), (700, 700), "{:?}", c);
+        }
+    }
+
+    #[test]
+    fn fallbacks_match_cap_height() {
+        let primary = TestFont::squares("A", 700).build();
+        let primary = Face::parse(&primary, 0).unwrap();
+        let fallback = TestFont::squares("B", 350).build();
+        assert_eq!(
+            fallback_scale(&primary, &Face::parse(&fallback, 0).unwrap()),
+            2.0
+        );
+        // Without a cap height, nor an H to measure it on, the em sizes match
+        let fallback = TestFont {
+            units_per_em: 2000,
+            cap_height: None,
+            ..TestFont::squares("B", 350)
+        }
+        .build();
+        assert_eq!(
+            fallback_scale(&primary, &Face::parse(&fallback, 0).unwrap()),
+            0.5
+        );
+    }
+
+    #[test]
+    fn blank_letters_are_an_error() {
+        let font = TestFont {
+            glyphs: vec![('A', Vec::new()), ('B', Vec::new())],
+            ..TestFont::squares("", 700)
+        }
+        .build();
+        assert!(matches!(
+            analyze(font, "AB", &Instance::default()),
+            Err(Error::NoOutline)
+        ));
+    }
+}
@@ -0,0 +1,149 @@
+//! Kerf compensation: the laser burns away a line of material along its path,
+//! so the paths are moved by half of its width away from the parts to keep.
+
+use crate::path::{self, Contour, Point};
+use crate::sheet::{LayerKind, Shape, Sheet};
+
+/// How far the contours of a layer move outward, for a `kerf` wide cut
+///
+/// Outlines enclose the material to keep and grow, the other cuts enclose
+/// the material to remove and shrink. Guides are not cut.
+fn growth(kind: LayerKind, kerf: f64) -> Option<f64> {
+    match kind {
+        LayerKind::Outline | LayerKind::Baffles => Some(kerf / 2.0),
+        LayerKind::Holes
+        | LayerKind::Minutes
+        | LayerKind::Letters
+        | LayerKind::Cable
+        | LayerKind::Keyholes => Some(-kerf / 2.0),
+        LayerKind::Leds | LayerKind::Margins | LayerKind::BoundingBoxes => None,
+    }
+}
+
+/// Move the contours of every shape to cut by half of `kerf`, in mm
+///
+/// Circles and rectangles keep their shape. Glyph outlines are turned into
+/// polygons within `tolerance` mm of their curves before being moved.
+pub fn compensate(sheet: &mut Sheet, kerf: f64, tolerance: f64) {
+    let scale = sheet.scale;
+    for layer in &mut sheet.layers {
+        let Some(growth) = growth(layer.kind, kerf) else {
+            continue;
+        };
+        for shape in &mut layer.shapes {
+            match shape {
+                Shape::Circle(circle) => circle.r += growth,
+                Shape::Rect(rect) => {
+                    rect.x -= growth;
+                    rect.y -= growth;
+                    rect.width += 2.0 * growth;
+                    rect.height += 2.0 * growth;
+                }
+                Shape::Polygon(polygon) => {
+                    // Whatever the way the polygon goes, grow the area inside
+                    let outward = if Contour::polygon(&polygon.points).signed_area() > 0.0 {
+                        growth
+                    } else {
+                        -growth
+                    };
+                    polygon.points = path::offset(&polygon.points, outward);
+                }
+                Shape::Path(p) => {
+                    // Outer contours go counterclockwise and holes clockwise,
+                    // so that moving all of them to their right grows the area
+                    let polygons: Vec<Vec<Point>> =
+                        path::regions(path::parse(&p.d), tolerance / scale)
+                            .iter()
+                            .flat_map(|r| std::iter::once(&r.outer).chain(&r.holes))
+                            .map(|c| path::offset(&c.flatten(tolerance / scale), growth / scale))
+                            .collect();
+                    p.d = path::polygons_path(&polygons);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sheet::{Circle, Layer, Path, Polygon, Rect};
+
+    #[test]
+    fn cuts_move_away_from_material() {
+        let mut sheet = Sheet {
+            scale: 0.01,
+            layers: vec![
+                Layer {
+                    kind: LayerKind::Outline,
+                    shapes: vec![Shape::Rect(Rect {
+                        x: 0.0,
+                        y: 0.0,
+                        width: 100.0,
+                        height: 100.0,
+                    })],
+                },
+                Layer {
+                    kind: LayerKind::Holes,
+                    shapes: vec![Shape::Circle(Circle {
+                        cx: 10.0,
+                        cy: 10.0,
+                        r: 2.0,
+                    })],
+                },
+                Layer {
+                    kind: LayerKind::Letters,
+                    // 1mm square with a 0.5mm counter, in font units
+                    shapes: vec![Shape::Path(Path {
+                        d: "M 0 0 L 100 0 L 100 100 L 0 100 Z M 25 25 L 25 75 L 75 75 L 75 25 Z"
+                            .to_string(),
+                        x: 50.0,
+                        y: 50.0,
+                    })],
+                },
+                Layer {
+                    kind: LayerKind::Baffles,
+                    // Clockwise on the sheet
+                    shapes: vec![Shape::Polygon(Polygon {
+                        points: vec![
+                            Point::new(0.0, 0.0),
+                            Point::new(0.0, 10.0),
+                            Point::new(10.0, 10.0),
+                            Point::new(10.0, 0.0),
+                        ],
+                    })],
+                },
+                Layer {
+                    kind: LayerKind::Leds,
+                    shapes: vec![Shape::Rect(Rect {
+                        x: 5.0,
+                        y: 5.0,
+                        width: 5.0,
+                        height: 5.0,
+                    })],
+                },
+            ],
+            width: 100.0,
+            height: 100.0,
+        };
+        compensate(&mut sheet, 0.2, 0.01);
+        let shapes: Vec<&Shape> = sheet.layers.iter().map(|l| &l.shapes[0]).collect();
+        match shapes[..] {
+            [Shape::Rect(outline), Shape::Circle(hole), Shape::Path(letter), Shape::Polygon(baffle), Shape::Rect(led)] =>
+            {
+                assert!((outline.x + 0.1).abs() < 1e-9);
+                assert!((outline.width - 100.2).abs() < 1e-9);
+                assert!((hole.r - 1.9).abs() < 1e-9);
+                // The letter is thinner, its counter larger
+                let contours = path::parse(&letter.d);
+                let areas: Vec<f64> = contours.iter().map(|c| c.signed_area().abs()).collect();
+                assert!((areas[0] - 80.0 * 80.0).abs() < 1e-6);
+                assert!((areas[1] - 70.0 * 70.0).abs() < 1e-6);
+                let area = Contour::polygon(&baffle.points).signed_area();
+                assert!((area.abs() - 10.2 * 10.2).abs() < 1e-9);
+                assert_eq!((led.x, led.width), (5.0, 5.0));
+            }
+            _ => panic!("shapes must keep their kind"),
+        }
+    }
+}
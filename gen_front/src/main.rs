@@ -1,10 +1,78 @@
-use clap::{Arg, Command};
+use clap::{value_parser, Arg, ArgAction, ArgMatches, Command};
+use horloge_core::Word;
+use std::io::Write;
 use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
 
+mod back;
+mod baffle;
 mod cover;
+mod design;
 mod dxf;
+mod enclosure;
+#[cfg(test)]
+mod fixtures;
 mod font;
+mod kerf;
+mod path;
+mod sheet;
+mod solve;
+mod stencil;
 mod svg;
+mod validate;
+
+#[derive(Debug, Error)]
+enum Error {
+    #[error("{path}: {source}")]
+    Design {
+        path: PathBuf,
+        source: design::Error,
+    },
+    #[error(transparent)]
+    Parameter(#[from] design::Error),
+    #[error("--{name} {reason}")]
+    Option {
+        name: &'static str,
+        reason: &'static str,
+    },
+    #[error("Cannot read font {path}: {source}")]
+    ReadFont {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("{path}: {source}")]
+    Font { path: PathBuf, source: font::Error },
+    #[error("{}: {source}", list(.paths))]
+    Fonts {
+        paths: Vec<PathBuf>,
+        source: font::Error,
+    },
+    #[error(transparent)]
+    Cover(#[from] cover::Error),
+    #[error(transparent)]
+    Baffle(#[from] baffle::Error),
+    #[error(transparent)]
+    Solve(#[from] solve::Error),
+    #[error("{0} phrase(s) cannot be spelled on the grid")]
+    Invalid(usize),
+    #[error("Output file {0} must end with .svg or .dxf")]
+    Extension(PathBuf),
+    #[error("Cannot write {path}: {source}")]
+    Write {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+/// Paths separated by commas
+fn list(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|p| p.display().to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
 
 /// Clap command definition
 fn command() -> Command {
@@ -12,6 +80,197 @@ fn command() -> Command {
         .version(env!("CARGO_PKG_VERSION"))
         .author("Boris Faure <boris@fau.re>")
         .about("Generate an SVG or a DXF file with a custom grid")
+        .arg(
+            Arg::new("design")
+                .long("design")
+                .value_name("DESIGN")
+                .global(true)
+                .value_parser(clap::builder::PathBufValueParser::new())
+                .help(
+                    "TOML file describing the grid and the dimensions [default: built-in design]",
+                ),
+        )
+        .arg(
+            Arg::new("panel-size")
+                .long("panel-size")
+                .value_name("MM")
+                .value_parser(value_parser!(f64))
+                .help("Outer size of the cover, the LED spacing is derived from it"),
+        )
+        .arg(
+            Arg::new("leds")
+                .long("leds")
+                .action(ArgAction::SetTrue)
+                .overrides_with("no-leds")
+                .help(
+                    "Draw the footprint of the LEDs in the output file [default: draw_leds feature for SVG, off for DXF]",
+                ),
+        )
+        .arg(
+            Arg::new("no-leds")
+                .long("no-leds")
+                .action(ArgAction::SetTrue)
+                .overrides_with("leds")
+                .help("Do not draw the footprint of the LEDs"),
+        )
+        .arg(
+            Arg::new("margins")
+                .long("margins")
+                .action(ArgAction::SetTrue)
+                .overrides_with("no-margins")
+                .help(
+                    "Draw the limit of the margins in the output file [default: draw_margins feature for SVG, off for DXF]",
+                ),
+        )
+        .arg(
+            Arg::new("no-margins")
+                .long("no-margins")
+                .action(ArgAction::SetTrue)
+                .overrides_with("margins")
+                .help("Do not draw the limit of the margins"),
+        )
+        .arg(
+            Arg::new("bounding-boxes")
+                .long("bounding-boxes")
+                .action(ArgAction::SetTrue)
+                .overrides_with("no-bounding-boxes")
+                .help(
+                    "Draw the bounding box of every letter [default: draw_bounding_box feature, SVG only]",
+                ),
+        )
+        .arg(
+            Arg::new("no-bounding-boxes")
+                .long("no-bounding-boxes")
+                .action(ArgAction::SetTrue)
+                .overrides_with("bounding-boxes")
+                .help("Do not draw the bounding box of every letter"),
+        )
+        .arg(
+            Arg::new("fallback")
+                .long("fallback")
+                .value_name("FONT")
+                .action(ArgAction::Append)
+                .value_parser(clap::builder::PathBufValueParser::new())
+                .help("Font to take the characters missing from TTF from, at its first face and default axes, can be repeated, tried in order"),
+        )
+        .arg(
+            Arg::new("face")
+                .long("face")
+                .value_name("INDEX")
+                .value_parser(value_parser!(u32))
+                .default_value("0")
+                .help("Face to use in a font collection (.ttc)"),
+        )
+        .arg(
+            Arg::new("font-weight")
+                .long("font-weight")
+                .value_name("WEIGHT")
+                .value_parser(value_parser!(f32))
+                .help("Weight of a variable font, wght axis [default: font default]"),
+        )
+        .arg(
+            Arg::new("font-width")
+                .long("font-width")
+                .value_name("PERCENT")
+                .value_parser(value_parser!(f32))
+                .help("Width of a variable font, wdth axis [default: font default]"),
+        )
+        .arg(
+            Arg::new("optical-size")
+                .long("optical-size")
+                .value_name("POINTS")
+                .value_parser(value_parser!(f32))
+                .help("Optical size of a variable font, opsz axis [default: font default]"),
+        )
+        .arg(
+            Arg::new("part")
+                .long("part")
+                .value_parser(["cover", "back", "box", "baffle"])
+                .default_value("cover")
+                .help("Part of the clock to generate"),
+        )
+        .arg(
+            Arg::new("curves")
+                .long("curves")
+                .value_parser(["splines", "polylines"])
+                .default_value("splines")
+                .help("How the DXF file describes the letters"),
+        )
+        .arg(
+            Arg::new("tolerance")
+                .long("tolerance")
+                .value_name("MM")
+                .value_parser(value_parser!(f64))
+                .default_value("0.05")
+                .help("Largest distance between the polylines and the letters"),
+        )
+        .arg(
+            Arg::new("kerf")
+                .long("kerf")
+                .value_name("MM")
+                .value_parser(value_parser!(f64))
+                .default_value("0")
+                .help("Width of the cut of the laser, compensated on every contour"),
+        )
+        .arg(
+            Arg::new("stencil")
+                .long("stencil")
+                .action(ArgAction::SetTrue)
+                .help("Add bridges holding the counters of the letters"),
+        )
+        .subcommand_negates_reqs(true)
+        .args_conflicts_with_subcommands(true)
+        .subcommand(
+            Command::new("validate")
+                .about("Check that every time can be spelled on the grid, in reading order"),
+        )
+        .subcommand(
+            Command::new("solve")
+                .about("Search a grid layout spelling every time of the language")
+                .arg(
+                    Arg::new("width")
+                        .long("width")
+                        .value_parser(value_parser!(usize))
+                        .help("Number of columns [default: width of the design grid]"),
+                )
+                .arg(
+                    Arg::new("height")
+                        .long("height")
+                        .value_parser(value_parser!(usize))
+                        .help("Number of rows [default: height of the design grid]"),
+                )
+                .arg(
+                    Arg::new("filler").long("filler").help(
+                        "Letters filling the unused cells, in reading order [default: random]",
+                    ),
+                )
+                .arg(
+                    Arg::new("seed")
+                        .long("seed")
+                        .value_parser(value_parser!(u64))
+                        .help("Seed of the random number generator [default: current time]"),
+                )
+                .arg(
+                    Arg::new("attempts")
+                        .long("attempts")
+                        .value_parser(value_parser!(usize))
+                        .default_value("1000")
+                        .help("Number of word orders to try"),
+                )
+                .arg(
+                    Arg::new("format")
+                        .long("format")
+                        .value_parser(["toml", "rust"])
+                        .default_value("toml")
+                        .help("Write the grid for a design file or for horloge-core"),
+                )
+                .arg(
+                    Arg::new("output")
+                        .long("output")
+                        .value_parser(clap::builder::PathBufValueParser::new())
+                        .help("File to write the grid to [default: standard output]"),
+                ),
+        )
         .arg(
             Arg::new("TTF")
                 .value_name("TTF")
@@ -30,30 +289,255 @@ fn command() -> Command {
         )
 }
 
-fn main() {
-    let matches = command() // requires `cargo` feature
-        .get_matches();
+/// Search a grid layout and write it to `out`, or to the output file
+///
+/// The seed and the report of the grid go to the standard error, so that the
+/// grid written to `out` can be redirected to a design file.
+fn solve_grid(matches: &ArgMatches, out: &mut impl Write) -> Result<(), Error> {
+    let seed = matches.get_one::<u64>("seed").copied().unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0)
+    });
+    let design = load_design(matches)?;
+    let options = solve::Options {
+        width: matches
+            .get_one::<usize>("width")
+            .copied()
+            .unwrap_or(design.width()),
+        height: matches
+            .get_one::<usize>("height")
+            .copied()
+            .unwrap_or(design.height()),
+        filler: matches
+            .get_one::<String>("filler")
+            .map(|f| f.chars().collect())
+            .unwrap_or_default(),
+        seed,
+        attempts: *matches.get_one::<usize>("attempts").unwrap(),
+    };
+    eprintln!("seed:{}", seed);
+    let grid = solve::solve(&solve::language_phrases(), Word::text, &options)?;
+    eprintln!("{}", validate::validate(&grid));
+    let formatted = match matches.get_one::<String>("format").unwrap().as_str() {
+        "rust" => solve::format_grid(&grid),
+        _ => solve::format_design_grid(&grid),
+    };
+    match matches.get_one::<PathBuf>("output") {
+        Some(file) => std::fs::write(file, formatted).map_err(|source| Error::Write {
+            path: file.clone(),
+            source,
+        })?,
+        None => out
+            .write_all(formatted.as_bytes())
+            .map_err(|source| Error::Write {
+                path: PathBuf::from("-"),
+                source,
+            })?,
+    }
+    Ok(())
+}
+
+/// Whether the `on`/`off` flag pair is set, `default` if none is given
+fn toggle(matches: &ArgMatches, on: &str, off: &str, default: bool) -> bool {
+    if matches.get_flag(on) {
+        true
+    } else if matches.get_flag(off) {
+        false
+    } else {
+        default
+    }
+}
+
+/// Load the design given on the command line, or the built-in one
+fn load_design(matches: &ArgMatches) -> Result<design::Design, Error> {
+    match matches.get_one::<PathBuf>("design") {
+        Some(path) => design::Design::load(path).map_err(|source| Error::Design {
+            path: path.clone(),
+            source,
+        }),
+        None => Ok(design::Design::default()),
+    }
+}
+
+/// Generate the part asked for on the command line
+fn run(matches: &ArgMatches) -> Result<(), Error> {
+    if let Some(matches) = matches.subcommand_matches("validate") {
+        let design = load_design(matches)?;
+        let report = validate::validate(&design.grid);
+        println!("{}", report);
+        if !report.is_ok() {
+            return Err(Error::Invalid(report.unreachable.len()));
+        }
+        return Ok(());
+    }
+    if let Some(matches) = matches.subcommand_matches("solve") {
+        return solve_grid(matches, &mut std::io::stdout());
+    }
+
+    let mut design = load_design(matches)?;
+    if let Some(size) = matches.get_one::<f64>("panel-size") {
+        design.fit_to_size(*size)?;
+    }
+    if matches.get_flag("stencil") {
+        design.enable_stencil();
+    }
+    let tolerance = *matches.get_one::<f64>("tolerance").unwrap();
+    if tolerance <= 0.0 {
+        return Err(Error::Option {
+            name: "tolerance",
+            reason: "must be positive",
+        });
+    }
+    let kerf = *matches.get_one::<f64>("kerf").unwrap();
+    if kerf < 0.0 {
+        return Err(Error::Option {
+            name: "kerf",
+            reason: "must not be negative",
+        });
+    }
+    let file = matches.get_one::<PathBuf>("FILE").unwrap();
+    let ext = match file.extension().and_then(|e| e.to_str()) {
+        Some(ext @ ("svg" | "dxf")) => ext,
+        _ => return Err(Error::Extension(file.clone())),
+    };
 
     let font = matches.get_one::<PathBuf>("TTF").unwrap();
-    let font_data = std::fs::read(font).unwrap();
-    let fa = font::analyze_font(font_data).unwrap();
+    let fallbacks: Vec<&PathBuf> = matches
+        .get_many::<PathBuf>("fallback")
+        .unwrap_or_default()
+        .collect();
+    let read = |path: &PathBuf| {
+        std::fs::read(path).map_err(|source| Error::ReadFont {
+            path: path.clone(),
+            source,
+        })
+    };
+    let font_data = read(font)?;
+    let fallback_data = fallbacks
+        .iter()
+        .map(|path| read(path))
+        .collect::<Result<Vec<Vec<u8>>, Error>>()?;
+    let instance = font::Instance {
+        face: *matches.get_one::<u32>("face").unwrap(),
+        axes: [
+            ("font-weight", font::WEIGHT),
+            ("font-width", font::WIDTH),
+            ("optical-size", font::OPTICAL_SIZE),
+        ]
+        .into_iter()
+        .filter_map(|(arg, tag)| matches.get_one::<f32>(arg).map(|v| (tag, *v)))
+        .collect(),
+    };
+    let fa = font::analyze_font(
+        font_data,
+        &fallback_data,
+        &design.characters(),
+        design.minutes.enabled.then_some(design.minutes.glyph),
+        &instance,
+    )
+    .map_err(|source| match source {
+        font::Error::Fallback { index, source } => Error::Font {
+            path: fallbacks[index].clone(),
+            source: font::Error::FaceParse(source),
+        },
+        // Missing from every font
+        source @ font::Error::MissingGlyphs(_) if !fallbacks.is_empty() => Error::Fonts {
+            paths: std::iter::once(font)
+                .chain(fallbacks.iter().copied())
+                .cloned()
+                .collect(),
+            source,
+        },
+        source => Error::Font {
+            path: font.clone(),
+            source,
+        },
+    })?;
     println!("y_max:{}", fa.y_max);
     println!("glyph_width_avg:{}", fa.glyph_width_avg);
 
-    let file = matches.get_one::<PathBuf>("FILE").unwrap();
-    if file.extension().is_none() {
-        panic!("File must have an extension");
+    // Guides would be cut, DXF files only get the ones asked for
+    let defaults = match ext {
+        "svg" => cover::Guides::from_features(),
+        _ => cover::Guides::default(),
+    };
+    let guides = cover::Guides {
+        leds: toggle(matches, "leds", "no-leds", defaults.leds),
+        margins: toggle(matches, "margins", "no-margins", defaults.margins),
+        bounding_boxes: toggle(
+            matches,
+            "bounding-boxes",
+            "no-bounding-boxes",
+            defaults.bounding_boxes,
+        ),
+    };
+    let curves = match matches.get_one::<String>("curves").unwrap().as_str() {
+        "polylines" => dxf::Curves::Polylines { tolerance },
+        _ => dxf::Curves::Splines,
+    };
+    let mut sheet = match matches.get_one::<String>("part").unwrap().as_str() {
+        "back" => back::generate(&cover::Sizes::compute(&fa, &design)?, &design),
+        "box" => enclosure::generate(&cover::Sizes::compute(&fa, &design)?, &design),
+        "baffle" => baffle::generate(&cover::Sizes::compute(&fa, &design)?, &design)?,
+        _ => cover::generate(fa, &design, guides)?,
+    };
+    if kerf > 0.0 {
+        kerf::compensate(&mut sheet, kerf, tolerance);
     }
-    let ext = file.extension().unwrap().to_str().unwrap();
     match ext {
-        "svg" => {}
-        "dxf" => {}
-        _ => panic!("File must end with .svg or .dxf"),
+        "svg" => svg::generate(file, sheet),
+        _ => dxf::generate(file, sheet, curves),
     }
-    let cover = cover::Cover::new(fa);
-    match ext {
-        "svg" => svg::generate(file, cover).unwrap(),
-        "dxf" => dxf::generate(file, cover).unwrap(),
-        _ => unreachable!(),
+    .map_err(|source| Error::Write {
+        path: file.clone(),
+        source,
+    })
+}
+
+fn main() {
+    let matches = command() // requires `cargo` feature
+        .get_matches();
+    if let Err(e) = run(&matches) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_with(args: &[&str]) -> Result<(), Error> {
+        let matches = command()
+            .try_get_matches_from(std::iter::once("gen_front").chain(args.iter().copied()))
+            .unwrap();
+        run(&matches)
+    }
+
+    #[test]
+    fn solved_grid_is_a_design() {
+        let matches = command()
+            .try_get_matches_from(["gen_front", "solve", "--seed", "1"])
+            .unwrap();
+        let mut out: Vec<u8> = Vec::new();
+        solve_grid(matches.subcommand_matches("solve").unwrap(), &mut out).unwrap();
+        let design = design::Design::parse(std::str::from_utf8(&out).unwrap()).unwrap();
+        assert_eq!(design.width(), design::Design::default().width());
+    }
+
+    #[test]
+    fn invalid_grid_is_an_error() {
+        assert!(run_with(&["validate"]).is_ok());
+        let file = std::env::temp_dir().join("gen_front_invalid_grid.toml");
+        std::fs::write(&file, "grid = [\"ILESTUNE\", \"DEUXHEUR\"]\n").unwrap();
+        let result = run_with(&["validate", "--design", file.to_str().unwrap()]);
+        std::fs::remove_file(&file).unwrap();
+        assert!(
+            matches!(result, Err(Error::Invalid(n)) if n > 0),
+            "{:?}",
+            result
+        );
     }
 }
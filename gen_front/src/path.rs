@@ -0,0 +1,646 @@
+//! Glyph outlines, as written by the font module, and Bézier curves.
+
+use std::fmt::Write;
+use std::str::SplitWhitespace;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Point {
+    pub fn new(x: f64, y: f64) -> Self {
+        Point { x, y }
+    }
+
+    /// Point at `t` on the way from `self` to `other`
+    pub fn lerp(self, other: Point, t: f64) -> Point {
+        Point {
+            x: self.x + (other.x - self.x) * t,
+            y: self.y + (other.y - self.y) * t,
+        }
+    }
+}
+
+/// Piece of a contour, starting at the end of the previous one
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Segment {
+    /// Straight line to the point
+    Line(Point),
+    /// Quadratic Bézier curve, TrueType outlines: control point, end
+    Quad(Point, Point),
+    /// Cubic Bézier curve, CFF outlines: two control points, end
+    Cubic(Point, Point, Point),
+}
+
+impl Segment {
+    /// Last point of the segment
+    pub fn end(self) -> Point {
+        match self {
+            Segment::Line(p) | Segment::Quad(_, p) | Segment::Cubic(_, _, p) => p,
+        }
+    }
+
+    /// Point at `t`, between 0 and 1, of the segment starting at `start`
+    pub fn point_at(self, start: Point, t: f64) -> Point {
+        match self {
+            Segment::Line(p) => start.lerp(p, t),
+            Segment::Quad(c, p) => quadratic_bezier(start, c, p, t),
+            Segment::Cubic(c1, c2, p) => cubic_bezier(start, c1, c2, p, t),
+        }
+    }
+
+    /// Control points and end of the same curve as a cubic Bézier curve
+    ///
+    /// Lines get their control points at a third and two thirds, quadratic
+    /// curves are degree-elevated, both exactly.
+    pub fn to_cubic(self, start: Point) -> [Point; 3] {
+        match self {
+            Segment::Line(p) => [start.lerp(p, 1.0 / 3.0), start.lerp(p, 2.0 / 3.0), p],
+            Segment::Quad(c, p) => [start.lerp(c, 2.0 / 3.0), p.lerp(c, 2.0 / 3.0), p],
+            Segment::Cubic(c1, c2, p) => [c1, c2, p],
+        }
+    }
+
+    /// Derivative at `t` of the segment starting at `start`
+    pub fn derivative_at(self, start: Point, t: f64) -> Point {
+        let [c1, c2, p] = self.to_cubic(start);
+        let t2 = 1.0 - t;
+        let (a, b, c) = (3.0 * t2 * t2, 6.0 * t2 * t, 3.0 * t * t);
+        Point {
+            x: a * (c1.x - start.x) + b * (c2.x - c1.x) + c * (p.x - c2.x),
+            y: a * (c1.y - start.y) + b * (c2.y - c1.y) + c * (p.y - c2.y),
+        }
+    }
+
+    /// Same segment, from its end to `start`
+    pub fn reversed(self, start: Point) -> Segment {
+        match self {
+            Segment::Line(_) => Segment::Line(start),
+            Segment::Quad(c, _) => Segment::Quad(c, start),
+            Segment::Cubic(c1, c2, _) => Segment::Cubic(c2, c1, start),
+        }
+    }
+
+    /// Number of chords needed to stay within `tolerance` of the segment
+    ///
+    /// A chord spanning `h` of the parameter of a curve whose second
+    /// derivative is at most `M` is at most `M * h² / 8` away from the curve.
+    pub fn chords(self, start: Point, tolerance: f64) -> usize {
+        // Largest second derivative
+        let second =
+            |a: Point, b: Point, c: Point| (a.x - 2.0 * b.x + c.x).hypot(a.y - 2.0 * b.y + c.y);
+        let m = match self {
+            Segment::Line(_) => return 1,
+            Segment::Quad(c, p) => 2.0 * second(start, c, p),
+            Segment::Cubic(c1, c2, p) => 6.0 * second(start, c1, c2).max(second(c1, c2, p)),
+        };
+        ((m / (8.0 * tolerance)).sqrt().ceil() as usize).max(1)
+    }
+
+    /// Same segment with every point transformed by `f`
+    pub fn map(self, f: impl Fn(Point) -> Point) -> Segment {
+        match self {
+            Segment::Line(p) => Segment::Line(f(p)),
+            Segment::Quad(c, p) => Segment::Quad(f(c), f(p)),
+            Segment::Cubic(c1, c2, p) => Segment::Cubic(f(c1), f(c2), f(p)),
+        }
+    }
+}
+
+/// Closed outline: the last segment ends on `start`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Contour {
+    pub start: Point,
+    pub segments: Vec<Segment>,
+}
+
+impl Contour {
+    /// Closed polygon going through `points`, in order
+    pub fn polygon(points: &[Point]) -> Contour {
+        Contour {
+            start: points[0],
+            segments: points[1..]
+                .iter()
+                .chain(&points[..1])
+                .map(|p| Segment::Line(*p))
+                .collect(),
+        }
+    }
+
+    /// Same contour with every point transformed by `f`
+    pub fn map(&self, f: impl Fn(Point) -> Point) -> Contour {
+        Contour {
+            start: f(self.start),
+            segments: self.segments.iter().map(|s| s.map(&f)).collect(),
+        }
+    }
+
+    /// Same contour, traversed the other way
+    pub fn reversed(&self) -> Contour {
+        let mut segments: Vec<Segment> = self
+            .segments_from()
+            .map(|(start, segment)| segment.reversed(start))
+            .collect();
+        segments.reverse();
+        Contour {
+            start: self.start,
+            segments,
+        }
+    }
+
+    /// Area enclosed by the contour, positive when it goes counterclockwise
+    /// with the y axis pointing up
+    ///
+    /// The area is half the integral of `x dy - y dx` along the contour, a
+    /// polynomial of degree 5 at most on every segment: the three point
+    /// Gauss-Legendre quadrature gives it exactly.
+    pub fn signed_area(&self) -> f64 {
+        const NODES: [(f64, f64); 3] = [
+            (0.112_701_665_379_258_3, 5.0 / 18.0),
+            (0.5, 8.0 / 18.0),
+            (0.887_298_334_620_741_7, 5.0 / 18.0),
+        ];
+        let integral: f64 = self
+            .segments_from()
+            .flat_map(|(start, segment)| {
+                NODES.iter().map(move |(t, weight)| {
+                    let p = segment.point_at(start, *t);
+                    let d = segment.derivative_at(start, *t);
+                    weight * (p.x * d.y - p.y * d.x)
+                })
+            })
+            .sum();
+        integral / 2.0
+    }
+
+    /// Points of a closed polygon staying within `tolerance` of the contour
+    ///
+    /// The start is not repeated at the end.
+    pub fn flatten(&self, tolerance: f64) -> Vec<Point> {
+        let mut points = vec![self.start];
+        for (start, segment) in self.segments_from() {
+            let n = segment.chords(start, tolerance);
+            points.extend((1..=n).map(|i| segment.point_at(start, i as f64 / n as f64)));
+        }
+        points.pop();
+        points
+    }
+
+    /// Every segment along with the point it starts from
+    pub fn segments_from(&self) -> impl Iterator<Item = (Point, &Segment)> {
+        let starts = std::iter::once(self.start).chain(self.segments.iter().map(|s| s.end()));
+        starts.zip(&self.segments)
+    }
+}
+
+/// Outer contour of a filled area, with the contours of its counters
+///
+/// The outer contour goes counterclockwise and the holes clockwise, with the
+/// y axis pointing up, so that both the even-odd and the nonzero rules fill
+/// the same area.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Region {
+    pub outer: Contour,
+    pub holes: Vec<Contour>,
+}
+
+/// Whether `p` is inside `polygon`, by the even-odd rule
+fn contains(polygon: &[Point], p: Point) -> bool {
+    let mut inside = false;
+    let mut j = polygon.len() - 1;
+    for (i, a) in polygon.iter().enumerate() {
+        let b = polygon[j];
+        if (a.y > p.y) != (b.y > p.y) && p.x < a.x + (p.y - a.y) / (b.y - a.y) * (b.x - a.x) {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+/// Group contours into regions, whatever their winding
+///
+/// A contour lies inside another when all of its points do: contours
+/// overlapping each other, as in unmerged outlines, are not nested. A contour
+/// nested an even number of levels deep is an outer contour, and a hole of
+/// one of the contours just around it otherwise. Islands inside holes, like
+/// the inside of a stencil ring, start new regions. Containment is checked on
+/// polygons within `tolerance` of the contours.
+pub fn regions(contours: Vec<Contour>, tolerance: f64) -> Vec<Region> {
+    let polygons: Vec<Vec<Point>> = contours.iter().map(|c| c.flatten(tolerance)).collect();
+    let inside = |i: usize, j: usize| polygons[i].iter().all(|p| contains(&polygons[j], *p));
+    let parents: Vec<Vec<usize>> = (0..contours.len())
+        .map(|i| {
+            (0..contours.len())
+                .filter(|j| *j != i && inside(i, *j) && !inside(*j, i))
+                .collect()
+        })
+        .collect();
+
+    // Nesting level of every contour, parents coming before their children
+    let mut order: Vec<usize> = (0..contours.len()).collect();
+    order.sort_by_key(|i| parents[*i].len());
+    let mut level: Vec<usize> = vec![0; contours.len()];
+    for &i in &order {
+        level[i] = parents[i].iter().map(|p| level[*p] + 1).max().unwrap_or(0);
+    }
+
+    // Same contour going counterclockwise when `ccw`, clockwise otherwise
+    let wound = |contour: &Contour, ccw: bool| {
+        if (contour.signed_area() > 0.0) == ccw {
+            contour.clone()
+        } else {
+            contour.reversed()
+        }
+    };
+    let mut regions: Vec<Region> = Vec::new();
+    // Index of the region of every outer contour
+    let mut region_of: Vec<Option<usize>> = vec![None; contours.len()];
+    for i in (0..contours.len()).filter(|i| level[*i] % 2 == 0) {
+        region_of[i] = Some(regions.len());
+        regions.push(Region {
+            outer: wound(&contours[i], true),
+            holes: Vec::new(),
+        });
+    }
+    for i in (0..contours.len()).filter(|i| level[*i] % 2 == 1) {
+        // The direct parent is the one nested one level less deep, and the
+        // contour an outer one of its own when there is none
+        match parents[i]
+            .iter()
+            .find(|p| level[**p] + 1 == level[i])
+            .and_then(|p| region_of[*p])
+        {
+            Some(parent) => regions[parent].holes.push(wound(&contours[i], false)),
+            None => regions.push(Region {
+                outer: wound(&contours[i], true),
+                holes: Vec::new(),
+            }),
+        }
+    }
+    regions
+}
+
+pub fn quadratic_bezier(p0: Point, p1: Point, p2: Point, t: f64) -> Point {
+    let t2 = 1.0 - t;
+    Point {
+        x: t2 * t2 * p0.x + 2.0 * t2 * t * p1.x + t * t * p2.x,
+        y: t2 * t2 * p0.y + 2.0 * t2 * t * p1.y + t * t * p2.y,
+    }
+}
+
+pub fn cubic_bezier(p0: Point, p1: Point, p2: Point, p3: Point, t: f64) -> Point {
+    let t2 = 1.0 - t;
+    let (a, b, c, d) = (t2 * t2 * t2, 3.0 * t2 * t2 * t, 3.0 * t2 * t * t, t * t * t);
+    Point {
+        x: a * p0.x + b * p1.x + c * p2.x + d * p3.x,
+        y: a * p0.y + b * p1.y + c * p2.y + d * p3.y,
+    }
+}
+
+/// Centroid of the area enclosed by `contours`, outer contours and holes
+/// going opposite ways, within `tolerance` of the curves
+///
+/// Returns `None` when the contours enclose no area.
+pub fn centroid(contours: &[Contour], tolerance: f64) -> Option<Point> {
+    let (mut area, mut x, mut y) = (0.0, 0.0, 0.0);
+    for polygon in contours.iter().map(|c| c.flatten(tolerance)) {
+        for i in 0..polygon.len() {
+            let (a, b) = (polygon[i], polygon[(i + 1) % polygon.len()]);
+            let cross = a.x * b.y - b.x * a.y;
+            area += cross / 2.0;
+            x += (a.x + b.x) * cross / 6.0;
+            y += (a.y + b.y) * cross / 6.0;
+        }
+    }
+    (area.abs() > f64::EPSILON).then(|| Point::new(x / area, y / area))
+}
+
+/// Polygon moved by `distance` to the right of its edges: outward for a
+/// counterclockwise polygon, inward for a clockwise one, with the y axis
+/// pointing up
+///
+/// Corners are mitered, and beveled when the miter would be more than twice
+/// `distance` long.
+pub fn offset(polygon: &[Point], distance: f64) -> Vec<Point> {
+    let mut points: Vec<Point> = polygon.to_vec();
+    points.dedup();
+    while points.len() > 1 && points[0] == points[points.len() - 1] {
+        points.pop();
+    }
+    let n = points.len();
+    // Unit normal on the right of the edge from `a` to `b`
+    let normal = |a: Point, b: Point| {
+        let length = (b.x - a.x).hypot(b.y - a.y);
+        Point::new((b.y - a.y) / length, (a.x - b.x) / length)
+    };
+    let mut result = Vec::with_capacity(n);
+    for i in 0..n {
+        let (previous, p, next) = (points[(i + n - 1) % n], points[i], points[(i + 1) % n]);
+        let (a, b) = (normal(previous, p), normal(p, next));
+        let cos = a.x * b.x + a.y * b.y;
+        // The miter is 1 / cos(angle / 2) = sqrt(2 / (1 + cos)) times as long
+        if 1.0 + cos < 0.5 {
+            result.push(Point::new(p.x + a.x * distance, p.y + a.y * distance));
+            result.push(Point::new(p.x + b.x * distance, p.y + b.y * distance));
+        } else {
+            let k = distance / (1.0 + cos);
+            result.push(Point::new(p.x + (a.x + b.x) * k, p.y + (a.y + b.y) * k));
+        }
+    }
+    result
+}
+
+/// Path data of closed `polygons`, their first point not repeated at the end
+pub fn polygons_path(polygons: &[Vec<Point>]) -> String {
+    let mut d = String::new();
+    for polygon in polygons {
+        write!(d, "M {} {} ", polygon[0].x, polygon[0].y).unwrap();
+        for p in &polygon[1..] {
+            write!(d, "L {} {} ", p.x, p.y).unwrap();
+        }
+        d.push_str("Z ");
+    }
+    d.pop();
+    d
+}
+
+fn next_point(tokens: &mut SplitWhitespace) -> Option<Point> {
+    let x = tokens.next()?.parse().ok()?;
+    let y = tokens.next()?.parse().ok()?;
+    Some(Point::new(x, y))
+}
+
+/// Parse path data made of absolute `M`, `L`, `Q`, `C` and `Z` commands, as
+/// written by the font module
+///
+/// Contours are closed with a line when their last point is not their start,
+/// whether or not they end with `Z`. Parsing stops at the first malformed
+/// command.
+pub fn parse(d: &str) -> Vec<Contour> {
+    let mut contours = Vec::new();
+    let mut current: Option<Contour> = None;
+    let mut tokens = d.split_whitespace();
+
+    let close = |contour: Option<Contour>, contours: &mut Vec<Contour>| {
+        if let Some(mut contour) = contour {
+            let last = contour.segments.last().map(|s| s.end());
+            if let Some(last) = last {
+                if last != contour.start {
+                    contour.segments.push(Segment::Line(contour.start));
+                }
+                contours.push(contour);
+            }
+        }
+    };
+
+    while let Some(cmd) = tokens.next() {
+        let segment = match cmd {
+            "M" => {
+                let Some(start) = next_point(&mut tokens) else {
+                    break;
+                };
+                close(current.take(), &mut contours);
+                current = Some(Contour {
+                    start,
+                    segments: Vec::new(),
+                });
+                continue;
+            }
+            "Z" | "z" => {
+                close(current.take(), &mut contours);
+                continue;
+            }
+            "L" => next_point(&mut tokens).map(Segment::Line),
+            "Q" => next_point(&mut tokens)
+                .zip(next_point(&mut tokens))
+                .map(|(c, p)| Segment::Quad(c, p)),
+            "C" => next_point(&mut tokens)
+                .zip(next_point(&mut tokens))
+                .zip(next_point(&mut tokens))
+                .map(|((c1, c2), p)| Segment::Cubic(c1, c2, p)),
+            _ => None,
+        };
+        match (segment, current.as_mut()) {
+            (Some(segment), Some(contour)) => contour.segments.push(segment),
+            _ => break,
+        }
+    }
+    close(current, &mut contours);
+    contours
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn centroid_of_an_l() {
+        // 10x10 square with its top right 8x8 corner cut out, as a hole
+        let l = parse("M 0 0 L 10 0 L 10 10 L 0 10 Z M 2 2 L 2 10 L 10 10 L 10 2 Z");
+        let c = centroid(&l, 0.01).unwrap();
+        // Moments of the square minus the ones of the hole
+        let expected = (100.0 * 5.0 - 64.0 * 6.0) / 36.0;
+        assert!((c.x - expected).abs() < 1e-9 && (c.y - expected).abs() < 1e-9);
+        assert_eq!(centroid(&parse("M 0 0 L 10 0 Z"), 0.01), None);
+    }
+
+    #[test]
+    fn offset_square() {
+        let square = [
+            Point::new(0.0, 0.0),
+            Point::new(10.0, 0.0),
+            Point::new(10.0, 10.0),
+            Point::new(0.0, 10.0),
+        ];
+        assert_eq!(
+            offset(&square, 1.0),
+            vec![
+                Point::new(-1.0, -1.0),
+                Point::new(11.0, -1.0),
+                Point::new(11.0, 11.0),
+                Point::new(-1.0, 11.0),
+            ]
+        );
+        let reversed: Vec<Point> = square.iter().rev().copied().collect();
+        assert_eq!(offset(&reversed, 1.0)[0], Point::new(1.0, 9.0));
+        // A sharp spike is beveled
+        let spike = [
+            Point::new(0.0, 0.0),
+            Point::new(10.0, 0.0),
+            Point::new(0.0, 1.0),
+        ];
+        let grown = offset(&spike, 0.1);
+        assert_eq!(grown.len(), 4);
+        assert!(grown.iter().all(|p| p.x < 10.2));
+    }
+
+    #[test]
+    fn parse_every_command() {
+        let contours = parse("M 0 0 L 10 0 Q 20 0 20 10 C 20 20 10 20 0 20 Z M 5 5 L 6 5 L 6 6 Z");
+        assert_eq!(contours.len(), 2);
+        let first = &contours[0];
+        assert_eq!(first.start, Point::new(0.0, 0.0));
+        assert_eq!(
+            first.segments,
+            vec![
+                Segment::Line(Point::new(10.0, 0.0)),
+                Segment::Quad(Point::new(20.0, 0.0), Point::new(20.0, 10.0)),
+                Segment::Cubic(
+                    Point::new(20.0, 20.0),
+                    Point::new(10.0, 20.0),
+                    Point::new(0.0, 20.0)
+                ),
+                // Closing line added by Z
+                Segment::Line(Point::new(0.0, 0.0)),
+            ]
+        );
+        assert_eq!(contours[1].segments.len(), 3);
+    }
+
+    /// Distance from `p` to the segment between `a` and `b`
+    fn distance_to_chord(p: Point, a: Point, b: Point) -> f64 {
+        let (dx, dy) = (b.x - a.x, b.y - a.y);
+        let t = ((p.x - a.x) * dx + (p.y - a.y) * dy) / (dx * dx + dy * dy);
+        let q = a.lerp(b, t.clamp(0.0, 1.0));
+        (p.x - q.x).hypot(p.y - q.y)
+    }
+
+    #[test]
+    fn flatten_within_tolerance() {
+        let contour = &parse("M 0 0 L 100 0 Q 200 0 200 100 C 200 180 150 250 60 200 Z")[0];
+        for tolerance in [1.0, 0.1, 0.01] {
+            let mut polygon = contour.flatten(tolerance);
+            assert_ne!(polygon.first(), polygon.last());
+            polygon.push(polygon[0]);
+            for (start, segment) in contour.segments_from() {
+                for i in 0..=100 {
+                    let p = segment.point_at(start, i as f64 / 100.0);
+                    let distance = polygon
+                        .windows(2)
+                        .map(|w| distance_to_chord(p, w[0], w[1]))
+                        .fold(f64::INFINITY, f64::min);
+                    assert!(distance <= tolerance, "{} > {}", distance, tolerance);
+                }
+            }
+        }
+        // Lines are kept as they are
+        assert_eq!(parse("M 0 0 L 1 0 L 1 1 Z")[0].flatten(0.01).len(), 3);
+    }
+
+    /// Square of side `size` at `x`, `y`, counterclockwise when `ccw`
+    fn square(x: f64, y: f64, size: f64, ccw: bool) -> Contour {
+        let d = if ccw {
+            format!(
+                "M {x} {y} L {} {y} L {} {} L {x} {} Z",
+                x + size,
+                x + size,
+                y + size,
+                y + size
+            )
+        } else {
+            format!(
+                "M {x} {y} L {x} {} L {} {} L {} {y} Z",
+                y + size,
+                x + size,
+                y + size,
+                x + size
+            )
+        };
+        parse(&d).remove(0)
+    }
+
+    #[test]
+    fn signed_area_of_curves() {
+        assert!((square(0.0, 0.0, 10.0, true).signed_area() - 100.0).abs() < 1e-9);
+        assert!((square(0.0, 0.0, 10.0, false).signed_area() + 100.0).abs() < 1e-9);
+        // Parabola y = x² between -1 and 1 closed by a line: 4/3
+        let parabola = &parse("M -1 1 Q 0 -1 1 1 Z")[0];
+        assert!((parabola.signed_area() - 4.0 / 3.0).abs() < 1e-12);
+        assert!((parabola.reversed().signed_area() + 4.0 / 3.0).abs() < 1e-12);
+        // Cubic and polygon of the flattened contour agree
+        let contour = &parse("M 0 0 L 100 0 C 200 180 150 250 60 200 Z")[0];
+        let polygon = contour.flatten(1e-4);
+        let shoelace: f64 = (0..polygon.len())
+            .map(|i| {
+                let (a, b) = (polygon[i], polygon[(i + 1) % polygon.len()]);
+                a.x * b.y - b.x * a.y
+            })
+            .sum::<f64>()
+            / 2.0;
+        assert!((contour.signed_area() - shoelace).abs() < 1.0);
+    }
+
+    #[test]
+    fn reversed_contour() {
+        let contour = &parse("M 0 0 L 10 0 Q 20 0 20 10 C 20 20 10 20 0 20 Z")[0];
+        let reversed = contour.reversed();
+        assert_eq!(reversed.start, contour.start);
+        assert_eq!(reversed.reversed(), *contour);
+        for i in 0..=10 {
+            let t = i as f64 / 10.0;
+            let a = contour.segments[1].point_at(contour.segments[0].end(), t);
+            let b = reversed.segments[2].point_at(reversed.segments[1].end(), 1.0 - t);
+            assert!((a.x - b.x).abs() < 1e-12 && (a.y - b.y).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn nested_regions() {
+        // A ring around an island, every contour wound the same way, and a
+        // separate square
+        let contours = vec![
+            square(2.0, 2.0, 6.0, true),
+            square(0.0, 0.0, 10.0, true),
+            square(4.0, 4.0, 2.0, true),
+            square(20.0, 0.0, 10.0, false),
+        ];
+        let regions = regions(contours, 0.01);
+        assert_eq!(regions.len(), 3);
+        assert_eq!(regions[0].outer.start, Point::new(0.0, 0.0));
+        assert_eq!(regions[0].holes.len(), 1);
+        assert!((regions[0].holes[0].signed_area() + 36.0).abs() < 1e-9);
+        assert!((regions[1].outer.signed_area() - 4.0).abs() < 1e-9);
+        assert!(regions[1].holes.is_empty());
+        assert!((regions[2].outer.signed_area() - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn overlapping_regions() {
+        // Two overlapping squares, each starting inside the other, with a
+        // counter inside both
+        let contours = vec![
+            parse("M 10 5 L 10 10 L 0 10 L 0 0 L 10 0 Z").remove(0),
+            square(3.0, 1.0, 10.0, false),
+            square(4.0, 4.0, 2.0, true),
+        ];
+        let regions = regions(contours, 0.01);
+        assert_eq!(regions.len(), 2);
+        assert!((regions[0].outer.signed_area() - 100.0).abs() < 1e-9);
+        assert!((regions[1].outer.signed_area() - 100.0).abs() < 1e-9);
+        // The counter is a hole of the first one around it
+        assert_eq!(regions[0].holes.len(), 1);
+        assert!((regions[0].holes[0].signed_area() + 4.0).abs() < 1e-9);
+        assert!(regions[1].holes.is_empty());
+    }
+
+    #[test]
+    fn elevated_curves_match() {
+        let start = Point::new(1.0, 2.0);
+        for segment in [
+            Segment::Line(Point::new(7.0, -3.0)),
+            Segment::Quad(Point::new(4.0, 9.0), Point::new(8.0, 1.0)),
+        ] {
+            let [c1, c2, end] = segment.to_cubic(start);
+            for i in 0..=10 {
+                let t = i as f64 / 10.0;
+                let expected = segment.point_at(start, t);
+                let found = cubic_bezier(start, c1, c2, end, t);
+                assert!((expected.x - found.x).abs() < 1e-12);
+                assert!((expected.y - found.y).abs() < 1e-12);
+            }
+        }
+    }
+}
@@ -0,0 +1,87 @@
+//! Documents to write as SVG or DXF files: shapes sorted in layers.
+
+use crate::path::Point;
+
+pub struct Circle {
+    pub cx: f64,
+    pub cy: f64,
+    pub r: f64,
+}
+pub struct Path {
+    pub d: String,
+    pub x: f64,
+    pub y: f64,
+}
+/// Axis-aligned rectangle, `x` and `y` being its top left corner
+pub struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+/// Closed polygon
+pub struct Polygon {
+    pub points: Vec<Point>,
+}
+pub enum Shape {
+    Circle(Circle),
+    Path(Path),
+    Rect(Rect),
+    Polygon(Polygon),
+}
+
+/// What the shapes of a layer represent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayerKind {
+    /// Outer edge of the part
+    Outline,
+    Holes,
+    Minutes,
+    Letters,
+    /// Footprint of the LEDs behind the letters
+    Leds,
+    /// Limit between the margins and the grid
+    Margins,
+    /// Bounding box of every letter
+    BoundingBoxes,
+    /// Strips of the light baffle
+    Baffles,
+    /// Slot for the power cable
+    Cable,
+    /// Keyholes to hang the clock
+    Keyholes,
+}
+
+impl LayerKind {
+    /// Name of the layer in the output files
+    pub fn name(self) -> &'static str {
+        match self {
+            LayerKind::Outline => "outline",
+            LayerKind::Holes => "holes",
+            LayerKind::Minutes => "minutes",
+            LayerKind::Letters => "letters",
+            LayerKind::Leds => "leds",
+            LayerKind::Margins => "margins",
+            LayerKind::BoundingBoxes => "bounding_boxes",
+            LayerKind::Baffles => "baffles",
+            LayerKind::Cable => "cable",
+            LayerKind::Keyholes => "keyholes",
+        }
+    }
+}
+
+/// Shapes of a given kind
+pub struct Layer {
+    pub kind: LayerKind,
+    pub shapes: Vec<Shape>,
+}
+
+/// Document of `width` by `height` mm, the y axis pointing down
+pub struct Sheet {
+    /// Scale of the glyph paths, from font units to mm
+    pub scale: f64,
+    /// Layers, in drawing order
+    pub layers: Vec<Layer>,
+    pub width: f64,
+    pub height: f64,
+}
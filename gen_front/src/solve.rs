@@ -0,0 +1,332 @@
+//! Search for a grid layout spelling every phrase in reading order.
+
+use horloge_core::{time_to_words, Word};
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("{0} must be lit both before and after {1}")]
+    Conflict(&'static str, &'static str),
+    #[error("Word {0} is longer than the grid width ({1})")]
+    TooLong(&'static str, usize),
+    #[error("{} must each be lit before the next one, and the last before the first", .0.join(", "))]
+    Cycle(Vec<&'static str>),
+    #[error("No layout found after {0} attempts")]
+    NoLayout(usize),
+}
+
+/// Solver parameters
+#[derive(Debug)]
+pub struct Options {
+    /// Number of columns
+    pub width: usize,
+    /// Number of rows
+    pub height: usize,
+    /// Letters used, in order, to fill the cells no word uses. Random letters
+    /// are picked when empty.
+    pub filler: Vec<char>,
+    /// Seed of the random number generator
+    pub seed: u64,
+    /// Number of word orders to try before giving up
+    pub attempts: usize,
+}
+
+/// Small xorshift generator, good enough to shuffle word orders
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        // xorshift gets stuck on zero
+        Rng(seed ^ 0x9E37_79B9_7F4A_7C15)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
+
+/// Order constraints between the words of a language
+struct Words {
+    texts: Vec<Vec<char>>,
+    names: Vec<&'static str>,
+    /// `before[a][b]` when `a` is lit before `b` in some phrase
+    before: Vec<Vec<bool>>,
+}
+
+impl Words {
+    fn new<W: Copy + Ord>(phrases: &[Vec<W>], text: impl Fn(W) -> &'static str) -> Self {
+        let words: Vec<W> = phrases
+            .iter()
+            .flatten()
+            .copied()
+            .collect::<BTreeSet<W>>()
+            .into_iter()
+            .collect();
+        let index = |w: &W| words.binary_search(w).unwrap();
+        let mut before = vec![vec![false; words.len()]; words.len()];
+        for phrase in phrases {
+            for (i, a) in phrase.iter().enumerate() {
+                for b in &phrase[i + 1..] {
+                    before[index(a)][index(b)] = true;
+                }
+            }
+        }
+        Words {
+            texts: words.iter().map(|w| text(*w).chars().collect()).collect(),
+            names: words.iter().map(|w| text(*w)).collect(),
+            before,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.texts.len()
+    }
+
+    /// Whether `a` and `b` are lit together by some phrase
+    fn together(&self, a: usize, b: usize) -> bool {
+        self.before[a][b] || self.before[b][a]
+    }
+
+    /// Random order of the words compatible with every phrase
+    ///
+    /// The first attempt always picks the first available word, so that
+    /// words keep the order of the language when possible. Fails when the
+    /// phrases order some words in a cycle.
+    fn order(&self, rng: &mut Rng, shuffle: bool) -> Result<Vec<usize>, Error> {
+        let n = self.len();
+        let mut pending: Vec<usize> = (0..n)
+            .map(|b| (0..n).filter(|a| self.before[*a][b]).count())
+            .collect();
+        let mut done = vec![false; n];
+        let mut order = Vec::with_capacity(n);
+        while order.len() < n {
+            let ready: Vec<usize> = (0..n).filter(|w| !done[*w] && pending[*w] == 0).collect();
+            if ready.is_empty() {
+                return Err(self.cycle(&done));
+            }
+            let w = if shuffle {
+                ready[rng.below(ready.len())]
+            } else {
+                ready[0]
+            };
+            done[w] = true;
+            order.push(w);
+            for (b, p) in pending.iter_mut().enumerate() {
+                if self.before[w][b] {
+                    *p -= 1;
+                }
+            }
+        }
+        Ok(order)
+    }
+
+    /// Words of a cycle among the words not `done`, when each of them has to
+    /// come after another one
+    fn cycle(&self, done: &[bool]) -> Error {
+        let n = self.len();
+        let previous = |b: usize| {
+            (0..n)
+                .find(|a| !done[*a] && self.before[*a][b])
+                .unwrap_or(b)
+        };
+        // Going back from any word ends up going around a cycle
+        let mut seen = vec![false; n];
+        let mut w = (0..n).find(|w| !done[*w]).unwrap_or(0);
+        while !seen[w] {
+            seen[w] = true;
+            w = previous(w);
+        }
+        let mut cycle = vec![self.names[w]];
+        let mut v = previous(w);
+        while v != w {
+            cycle.push(self.names[v]);
+            v = previous(v);
+        }
+        cycle.reverse();
+        Error::Cycle(cycle)
+    }
+}
+
+/// Place the words in `order`, each at the first cell satisfying the
+/// phrases already placed
+///
+/// Words never lit together may share letters, like MIDI and DIX.
+fn place(words: &Words, order: &[usize], width: usize, height: usize) -> Option<Vec<Option<char>>> {
+    let mut cells: Vec<Option<char>> = vec![None; width * height];
+    let mut starts: Vec<Option<usize>> = vec![None; words.len()];
+    for &w in order {
+        let text = &words.texts[w];
+        // Words lit with this one must end before it starts
+        let after = (0..words.len())
+            .filter(|o| words.together(*o, w))
+            .filter_map(|o| starts[o].map(|s| s + words.texts[o].len()))
+            .max()
+            .unwrap_or(0);
+        let start = (after..cells.len()).find(|p| {
+            p % width + text.len() <= width
+                && text
+                    .iter()
+                    .enumerate()
+                    .all(|(i, c)| cells[p + i].map_or(true, |l| l == *c))
+        })?;
+        for (i, c) in text.iter().enumerate() {
+            cells[start + i] = Some(*c);
+        }
+        starts[w] = Some(start);
+    }
+    Some(cells)
+}
+
+/// Search a layout of `options.width` by `options.height` letters where
+/// every phrase can be read left to right, top to bottom
+pub fn solve<W: Copy + Ord>(
+    phrases: &[Vec<W>],
+    text: impl Fn(W) -> &'static str,
+    options: &Options,
+) -> Result<Vec<Vec<char>>, Error> {
+    let words = Words::new(phrases, text);
+    for a in 0..words.len() {
+        if words.texts[a].len() > options.width {
+            return Err(Error::TooLong(words.names[a], options.width));
+        }
+        for b in 0..a {
+            if words.before[a][b] && words.before[b][a] {
+                return Err(Error::Conflict(words.names[a], words.names[b]));
+            }
+        }
+    }
+
+    let mut rng = Rng::new(options.seed);
+    let mut cells = None;
+    for attempt in 0..options.attempts {
+        let order = words.order(&mut rng, attempt > 0)?;
+        cells = place(&words, &order, options.width, options.height);
+        if cells.is_some() {
+            break;
+        }
+    }
+    let cells = cells.ok_or(Error::NoLayout(options.attempts))?;
+
+    let mut filler = options.filler.iter().cycle();
+    let grid = cells
+        .chunks(options.width)
+        .map(|row| {
+            row.iter()
+                .map(|cell| match cell {
+                    Some(c) => *c,
+                    None => match filler.next() {
+                        Some(c) => *c,
+                        None => (b'A' + rng.below(26) as u8) as char,
+                    },
+                })
+                .collect()
+        })
+        .collect();
+    Ok(grid)
+}
+
+/// Every phrase of the active language, once
+pub fn language_phrases() -> Vec<Vec<Word>> {
+    let mut phrases: Vec<Vec<Word>> = (0..24)
+        .flat_map(|hour| (0..60).map(move |minute| time_to_words(hour, minute)))
+        .map(|phrase| phrase.iter().collect())
+        .collect();
+    phrases.sort();
+    phrases.dedup();
+    phrases
+}
+
+/// Format a grid the way `horloge-core` declares it
+pub fn format_grid(grid: &[Vec<char>]) -> String {
+    let mut out = String::from("pub const GRID: Grid = [\n");
+    for row in grid {
+        let letters: Vec<String> = row.iter().map(|c| format!("{:?}", c)).collect();
+        writeln!(out, "    [{}],", letters.join(", ")).unwrap();
+    }
+    out.push_str("];\n");
+    out
+}
+
+/// Format a grid the way design files declare it
+pub fn format_design_grid(grid: &[Vec<char>]) -> String {
+    let mut out = String::from("grid = [\n");
+    for row in grid {
+        let letters: String = row.iter().collect();
+        writeln!(out, "    {:?},", letters).unwrap();
+    }
+    out.push_str("]\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validate::validate;
+    use horloge_core::{GRID_HEIGHT, GRID_WIDTH};
+
+    fn options(width: usize, height: usize) -> Options {
+        Options {
+            width,
+            height,
+            filler: Vec::new(),
+            seed: 1,
+            attempts: 200,
+        }
+    }
+
+    #[test]
+    fn solves_builtin_size() {
+        let grid = solve(
+            &language_phrases(),
+            Word::text,
+            &options(GRID_WIDTH, GRID_HEIGHT),
+        )
+        .unwrap();
+        assert_eq!(grid.len(), GRID_HEIGHT);
+        assert!(grid.iter().all(|row| row.len() == GRID_WIDTH));
+        let report = validate(&grid);
+        assert!(report.is_ok(), "{}\n{}", report, format_grid(&grid));
+    }
+
+    #[test]
+    fn too_small() {
+        assert!(solve(&language_phrases(), Word::text, &options(GRID_WIDTH, 3)).is_err());
+    }
+
+    #[test]
+    fn filler_in_reading_order() {
+        let mut opts = options(GRID_WIDTH, GRID_HEIGHT + 1);
+        opts.filler = vec!['*'];
+        let grid = solve(&language_phrases(), Word::text, &opts).unwrap();
+        assert!(grid.last().unwrap().iter().all(|c| *c == '*'));
+    }
+
+    #[test]
+    fn order_cycle() {
+        let phrases = vec![
+            vec!["UN", "DEUX"],
+            vec!["DEUX", "TROIS"],
+            vec!["TROIS", "UN"],
+        ];
+        match solve(&phrases, |w| w, &options(GRID_WIDTH, GRID_HEIGHT)) {
+            Err(Error::Cycle(words)) => {
+                assert_eq!(words.len(), 3);
+                // Every word comes before the next one in some phrase
+                for (i, a) in words.iter().enumerate() {
+                    let b = words[(i + 1) % words.len()];
+                    assert!(phrases.contains(&vec![*a, b]), "{:?}", words);
+                }
+            }
+            other => panic!("{:?}", other),
+        }
+    }
+}
@@ -0,0 +1,220 @@
+//! Stencil bridges holding the counters of the letters once cut through.
+
+use crate::path::{self, Point};
+
+/// Closed polygon, its first point not repeated at the end
+type Ring = Vec<Point>;
+
+/// Point where the segment from `a` to `b` crosses the vertical line at `x`
+fn crossing(a: Point, b: Point, x: f64) -> Point {
+    a.lerp(b, (x - a.x) / (b.x - a.x))
+}
+
+/// Part of the area enclosed by `rings` on the left of the vertical line at
+/// `x` when `left`, on its right otherwise
+///
+/// The rings must be wound like regions: outer rings counterclockwise and
+/// holes clockwise. Every ring crossing the line is cut into chains, which are
+/// joined along the line: going up the line, the crossings alternate between
+/// entering and leaving the area, so each chain leaving the kept side is
+/// followed by the chain entering it next along the line.
+fn clip(rings: &[Ring], x: f64, left: bool) -> Vec<Ring> {
+    let inside = |p: Point| if left { p.x < x } else { p.x > x };
+    let mut result: Vec<Ring> = Vec::new();
+    let mut chains: Vec<Vec<Point>> = Vec::new();
+    for ring in rings {
+        let n = ring.len();
+        let Some(first_out) = (0..n).find(|i| !inside(ring[*i])) else {
+            result.push(ring.clone());
+            continue;
+        };
+        let mut chain: Vec<Point> = Vec::new();
+        for k in 1..=n {
+            let a = ring[(first_out + k - 1) % n];
+            let b = ring[(first_out + k) % n];
+            match (inside(a), inside(b)) {
+                (false, true) => chain = vec![crossing(a, b, x), b],
+                (true, true) => chain.push(b),
+                (true, false) => {
+                    chain.push(crossing(a, b, x));
+                    chains.push(std::mem::take(&mut chain));
+                }
+                (false, false) => {}
+            }
+        }
+    }
+
+    // Ends of the chains along the line: (y, chain, whether it is its start)
+    let mut ends: Vec<(f64, usize, bool)> = chains
+        .iter()
+        .enumerate()
+        .flat_map(|(i, c)| [(c[0].y, i, true), (c[c.len() - 1].y, i, false)])
+        .collect();
+    ends.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let mut exit = vec![0; chains.len()];
+    for (k, (_, chain, start)) in ends.iter().enumerate() {
+        if !start {
+            exit[*chain] = k;
+        }
+    }
+
+    let mut used = vec![false; chains.len()];
+    for first in 0..chains.len() {
+        let mut ring: Ring = Vec::new();
+        let mut chain = first;
+        while !used[chain] {
+            used[chain] = true;
+            ring.extend(&chains[chain]);
+            // The crossings pair up along the line: 0 with 1, 2 with 3...
+            let (_, next, start) = ends[exit[chain] ^ 1];
+            if !start {
+                // Badly wound rings, stop there rather than loop forever
+                break;
+            }
+            chain = next;
+        }
+        if ring.len() > 2 {
+            result.push(ring);
+        }
+    }
+    result
+}
+
+/// Horizontal extent of a ring
+fn x_range(ring: &[Point]) -> (f64, f64) {
+    ring.iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), p| {
+            (min.min(p.x), max.max(p.x))
+        })
+}
+
+/// Add bridges of `width` across every counter of the outline `d`
+///
+/// Every counter gets a vertical bridge through its middle, joining it to the
+/// rest of the cover at its top and its bottom. Counters side by side, like
+/// the ones of B, share their bridge. Outlines with counters are turned into
+/// polygons within `tolerance` of their curves, the others are returned as
+/// they are. `width` and `tolerance` are in the units of the outline.
+///
+/// Returns the new outline and the number of counters too narrow for a
+/// bridge.
+pub fn bridge(d: &str, width: f64, tolerance: f64) -> (String, usize) {
+    let regions = path::regions(path::parse(d), tolerance);
+    if regions.iter().all(|r| r.holes.is_empty()) {
+        return (d.to_string(), 0);
+    }
+
+    let mut narrow = 0;
+    let mut rings: Vec<Ring> = Vec::new();
+    for region in regions {
+        let holes: Vec<Ring> = region.holes.iter().map(|h| h.flatten(tolerance)).collect();
+        // Position of the bridges, as the left edge of the strips
+        let mut strips: Vec<f64> = Vec::new();
+        for hole in &holes {
+            let (min, max) = x_range(hole);
+            if max - min <= width {
+                narrow += 1;
+            } else if !strips.iter().any(|s| *s > min && s + width < max) {
+                strips.push((min + max - width) / 2.0);
+            }
+        }
+        let mut pieces: Vec<Ring> = vec![region.outer.flatten(tolerance)];
+        pieces.extend(holes);
+        for strip in strips {
+            let mut cut = clip(&pieces, strip, true);
+            cut.extend(clip(&pieces, strip + width, false));
+            pieces = cut;
+        }
+        rings.extend(pieces);
+    }
+
+    (path::polygons_path(&rings), narrow)
+}
+
+/// Part of the outline `d` above the horizontal line at `y`, its curves
+/// turned into polygons within `tolerance`
+///
+/// The outline is clipped like along a vertical line, with x and y swapped:
+/// rings are reversed too so that they keep their winding.
+pub fn clip_below(d: &str, y: f64, tolerance: f64) -> String {
+    let transpose =
+        |ring: &[Point]| -> Ring { ring.iter().rev().map(|p| Point::new(p.y, p.x)).collect() };
+    let rings: Vec<Ring> = path::regions(path::parse(d), tolerance)
+        .iter()
+        .flat_map(|r| std::iter::once(&r.outer).chain(&r.holes))
+        .map(|c| transpose(&c.flatten(tolerance)))
+        .collect();
+    let clipped: Vec<Ring> = clip(&rings, y, false)
+        .iter()
+        .map(|ring| transpose(ring))
+        .collect();
+    path::polygons_path(&clipped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::Contour;
+
+    fn rings(d: &str) -> Vec<Ring> {
+        path::parse(d).iter().map(|c| c.flatten(0.01)).collect()
+    }
+
+    #[test]
+    fn square_ring_is_split() {
+        // 10x10 square with a 6x6 counter, wound the same way as TrueType
+        let d = "M 0 0 L 0 10 L 10 10 L 10 0 Z M 2 2 L 2 8 L 8 8 L 8 2 Z";
+        let (bridged, narrow) = bridge(d, 1.0, 0.01);
+        assert_eq!(narrow, 0);
+        let pieces = rings(&bridged);
+        // The left and right halves of the ring, without any counter
+        assert_eq!(pieces.len(), 2);
+        for piece in &pieces {
+            let area = Contour::polygon(piece).signed_area();
+            assert!((area - 30.0).abs() < 1e-9, "{}", area);
+            assert!(piece.iter().all(|p| p.x <= 4.5 || p.x >= 5.5));
+        }
+    }
+
+    #[test]
+    fn stacked_counters_share_a_bridge() {
+        // Like a B: two counters one above the other
+        let d = "M 0 0 L 10 0 L 10 20 L 0 20 Z \
+                 M 2 2 L 2 8 L 8 8 L 8 2 Z \
+                 M 2 12 L 2 18 L 8 18 L 8 12 Z";
+        let (bridged, _) = bridge(d, 1.0, 0.01);
+        let pieces = rings(&bridged);
+        assert_eq!(pieces.len(), 2);
+        let total: f64 = pieces
+            .iter()
+            .map(|p| Contour::polygon(p).signed_area())
+            .sum();
+        // Ring minus the bridge, crossing 8 units of it
+        assert!((total - (200.0 - 72.0 - 8.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn descender_is_clipped() {
+        // Ring going 4 units below the baseline
+        let d = "M 0 -4 L 10 -4 L 10 10 L 0 10 Z M 2 2 L 2 8 L 8 8 L 8 2 Z";
+        let pieces = rings(&clip_below(d, -2.0, 0.01));
+        assert!(pieces.iter().flatten().all(|p| p.y >= -2.0));
+        let total: f64 = pieces
+            .iter()
+            .map(|p| Contour::polygon(p).signed_area())
+            .sum();
+        assert!((total - (120.0 - 36.0)).abs() < 1e-9, "{}", total);
+    }
+
+    #[test]
+    fn letters_without_counters_are_kept() {
+        let d = "M 0 0 L 10 0 Q 12 5 10 10 L 0 10 Z";
+        assert_eq!(bridge(d, 1.0, 0.01), (d.to_string(), 0));
+        let (_, narrow) = bridge(
+            "M 0 0 L 10 0 L 10 10 L 0 10 Z M 4 4 L 4.5 4 L 4.5 6 Z",
+            1.0,
+            0.01,
+        );
+        assert_eq!(narrow, 1);
+    }
+}
@@ -1,6 +1,6 @@
-//! Generate the SVG file from a Cover structure
+//! Generate the SVG file from a Sheet structure
 
-use crate::cover::{Circle, Cover, Path, Shape};
+use crate::sheet::{Circle, Layer, LayerKind, Path, Polygon, Rect, Shape, Sheet};
 use quick_xml::events::{BytesDecl, Event};
 use quick_xml::Writer;
 use std::fs::File;
@@ -11,38 +11,18 @@ use std::path::PathBuf;
 const FILL_COLOR: &str = "black";
 //const FILL_COLOR: &str = "darkorange";
 
-///// Draw the LEDs in the grid
-//#[cfg(feature = "draw_leds")]
-//fn draw_leds(writer: &mut Writer<BufWriter<File>>, doc: &Sizes) -> IoResult<()> {
-//    // Compute the horizontal offset to center the LEDs
-//    let square_width = doc.document_width - 2.0 * MARGIN;
-//    let right_offset = (square_width - (GRID_WIDTH as f64 - 1.) * LED_SPACING - LED_SIZE) / 2.;
-//    // Compute the vertical spacing between the LEDs
-//    let square_height = doc.document_height - 2.0 * MARGIN;
-//    let vert_spacing = square_height / (GRID_HEIGHT as f64);
-//
-//    let base_y = MARGIN + vert_spacing / 2. - LED_SIZE / 2.;
-//    let base_x = MARGIN + right_offset;
-//    let led_size = LED_SIZE.to_string();
-//    for y in 0..GRID_HEIGHT {
-//        for x in 0..GRID_WIDTH {
-//            let x_str = (x as f64 * LED_SPACING + base_x).to_string();
-//            let y_str = (y as f64 * vert_spacing + base_y).to_string();
-//            let attrs = vec![
-//                ("x", x_str.as_str()),
-//                ("y", y_str.as_str()),
-//                ("width", led_size.as_str()),
-//                ("height", led_size.as_str()),
-//                ("fill", "red"),
-//            ];
-//            writer
-//                .create_element("rect")
-//                .with_attributes(attrs.into_iter())
-//                .write_empty()?;
-//        }
-//    }
-//    Ok(())
-//}
+/// Stroke width of the rectangles and polygons, in mm
+const LINE_STROKE_WIDTH: &str = "0.3";
+
+/// Color of the shapes of a layer
+fn layer_color(kind: LayerKind) -> &'static str {
+    match kind {
+        LayerKind::Leds => "red",
+        LayerKind::Margins => "blue",
+        LayerKind::BoundingBoxes => "green",
+        _ => FILL_COLOR,
+    }
+}
 
 /// Draw a circle
 fn draw_circle(
@@ -66,7 +46,7 @@ fn draw_circle(
     ];
     writer
         .create_element("circle")
-        .with_attributes(attrs.into_iter())
+        .with_attributes(attrs)
         .write_empty()?;
     Ok(())
 }
@@ -93,7 +73,54 @@ fn draw_path(
     ];
     writer
         .create_element("path")
-        .with_attributes(attrs.into_iter())
+        .with_attributes(attrs)
+        .write_empty()?;
+    Ok(())
+}
+
+/// Draw the outline of a rectangle
+fn draw_rect(writer: &mut Writer<BufWriter<File>>, rect: Rect, color: &str) -> IoResult<()> {
+    let x_str = rect.x.to_string();
+    let y_str = rect.y.to_string();
+    let width_str = rect.width.to_string();
+    let height_str = rect.height.to_string();
+    let attrs = vec![
+        ("x", x_str.as_str()),
+        ("y", y_str.as_str()),
+        ("width", width_str.as_str()),
+        ("height", height_str.as_str()),
+        ("stroke", color),
+        ("stroke-width", LINE_STROKE_WIDTH),
+        ("fill", "none"),
+    ];
+    writer
+        .create_element("rect")
+        .with_attributes(attrs)
+        .write_empty()?;
+    Ok(())
+}
+
+/// Draw the outline of a polygon
+fn draw_polygon(
+    writer: &mut Writer<BufWriter<File>>,
+    polygon: Polygon,
+    color: &str,
+) -> IoResult<()> {
+    let points: Vec<String> = polygon
+        .points
+        .iter()
+        .map(|p| format!("{},{}", p.x, p.y))
+        .collect();
+    let points = points.join(" ");
+    let attrs = vec![
+        ("points", points.as_str()),
+        ("stroke", color),
+        ("stroke-width", LINE_STROKE_WIDTH),
+        ("fill", "none"),
+    ];
+    writer
+        .create_element("polygon")
+        .with_attributes(attrs)
         .write_empty()?;
     Ok(())
 }
@@ -112,20 +139,47 @@ fn draw_shape(
         Shape::Path(p) => {
             draw_path(writer, p, scale, fill_color)?;
         }
+        Shape::Rect(r) => {
+            draw_rect(writer, r, fill_color)?;
+        }
+        Shape::Polygon(p) => {
+            draw_polygon(writer, p, fill_color)?;
+        }
     }
     Ok(())
 }
 
+/// Draw a layer as a named group, seen as a layer by Inkscape
+fn draw_layer(writer: &mut Writer<BufWriter<File>>, layer: Layer, scale: f64) -> IoResult<()> {
+    let name = layer.kind.name();
+    let attrs = vec![
+        ("id", name),
+        ("inkscape:label", name),
+        ("inkscape:groupmode", "layer"),
+    ];
+    let color = layer_color(layer.kind);
+    writer
+        .create_element("g")
+        .with_attributes(attrs)
+        .write_inner_content(|writer| {
+            for shape in layer.shapes {
+                draw_shape(writer, shape, scale, color)?;
+            }
+            Ok(())
+        })?;
+    Ok(())
+}
+
 /// Generate the SVG file
-pub fn generate(file: &PathBuf, cover: Cover) -> IoResult<()> {
+pub fn generate(file: &PathBuf, sheet: Sheet) -> IoResult<()> {
     let mut writer = Writer::new_with_indent(BufWriter::new(File::create(file)?), b' ', 2);
     writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
 
-    let scale = cover.scale;
+    let scale = sheet.scale;
     println!("scale:{}", scale);
-    let width_mm = format!("{}mm", cover.width);
-    let height_mm = format!("{}mm", cover.height);
-    let view_box = format!("0 0 {} {}", cover.width, cover.height);
+    let width_mm = format!("{}mm", sheet.width);
+    let height_mm = format!("{}mm", sheet.height);
+    let view_box = format!("0 0 {} {}", sheet.width, sheet.height);
     let svg_attrs = vec![
         ("width", width_mm.as_str()),
         ("height", height_mm.as_str()),
@@ -134,25 +188,19 @@ pub fn generate(file: &PathBuf, cover: Cover) -> IoResult<()> {
         ("id", "svg"),
         ("xmlns", "http://www.w3.org/2000/svg"),
         ("xmlns:svg", "http://www.w3.org/2000/svg"),
+        (
+            "xmlns:inkscape",
+            "http://www.inkscape.org/namespaces/inkscape",
+        ),
     ];
 
     writer
         .create_element("svg")
-        .with_attributes(svg_attrs.into_iter())
+        .with_attributes(svg_attrs)
         .write_inner_content(|writer| {
-            for shape in cover.shapes {
-                draw_shape(writer, shape, scale, FILL_COLOR)?;
+            for layer in sheet.layers {
+                draw_layer(writer, layer, scale)?;
             }
-            //#[cfg(feature = "draw_leds")]
-            //draw_leds(writer, &sizes)?;
-            //#[cfg(feature = "draw_margins")]
-            //draw_margins(writer, &sizes)?;
-            //draw_minutes(writer, FLOWER, &font, &sizes, scale)?;
-            //draw_holes(writer, &sizes)?;
-            //writer
-            //    .create_element("g")
-            //    .with_attributes(vec![("id", "grid")].into_iter())
-            //    .write_inner_content(|w| write_grid(w, font, &sizes, scale))?;
             Ok(())
         })?;
     Ok(())
@@ -0,0 +1,234 @@
+//! Check that every time can be spelled on a grid, in reading order.
+
+use horloge_core::time_to_words;
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// Position of a letter on the grid, as `(row, column)`
+pub type Cell = (usize, usize);
+
+/// A phrase that cannot be spelled on the grid
+#[derive(Debug)]
+pub struct Unreachable {
+    /// First time displaying the phrase, as `(hour, minute)`
+    pub time: (u8, u8),
+    /// Words of the phrase
+    pub phrase: Vec<&'static str>,
+    /// First word that could not be found after the previous ones
+    pub word: &'static str,
+}
+
+/// A word that can be lit at several places for a given phrase
+#[derive(Debug)]
+pub struct Ambiguous {
+    /// First time displaying the phrase, as `(hour, minute)`
+    pub time: (u8, u8),
+    /// Words of the phrase
+    pub phrase: Vec<&'static str>,
+    /// Ambiguous word
+    pub word: &'static str,
+    /// Positions of the first letter of every candidate
+    pub candidates: Vec<Cell>,
+}
+
+/// Result of the validation of a grid
+#[derive(Debug, Default)]
+pub struct Report {
+    /// Phrases that cannot be spelled
+    pub unreachable: Vec<Unreachable>,
+    /// Words with more than one possible position
+    pub ambiguous: Vec<Ambiguous>,
+    /// Letters never lit, with their position
+    pub unused: Vec<(Cell, char)>,
+}
+
+impl Report {
+    /// Whether every time can be displayed
+    pub fn is_ok(&self) -> bool {
+        self.unreachable.is_empty()
+    }
+}
+
+fn format_time(f: &mut fmt::Formatter<'_>, time: (u8, u8), phrase: &[&str]) -> fmt::Result {
+    write!(f, "{:02}:{:02} \"{}\"", time.0, time.1, phrase.join(" "))
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Unreachable phrases: {}", self.unreachable.len())?;
+        for u in &self.unreachable {
+            write!(f, "  ")?;
+            format_time(f, u.time, &u.phrase)?;
+            writeln!(f, ": {} not found", u.word)?;
+        }
+        writeln!(f, "Ambiguous matches: {}", self.ambiguous.len())?;
+        for a in &self.ambiguous {
+            write!(f, "  ")?;
+            format_time(f, a.time, &a.phrase)?;
+            write!(f, ": {} at", a.word)?;
+            for (row, col) in &a.candidates {
+                write!(f, " ({}, {})", row, col)?;
+            }
+            writeln!(f)?;
+        }
+        write!(f, "Unused letters: {}", self.unused.len())?;
+        for ((row, col), c) in &self.unused {
+            write!(f, "\n  {} at ({}, {})", c, row, col)?;
+        }
+        Ok(())
+    }
+}
+
+/// Positions of every occurrence of `word` on the grid, in reading order
+fn occurrences<R: AsRef<[char]>>(grid: &[R], word: &str) -> Vec<Cell> {
+    let letters: Vec<char> = word.chars().collect();
+    let mut found = Vec::new();
+    for (row, line) in grid.iter().enumerate() {
+        let line = line.as_ref();
+        if line.len() < letters.len() {
+            continue;
+        }
+        for col in 0..=line.len() - letters.len() {
+            if line[col..col + letters.len()] == letters[..] {
+                found.push((row, col));
+            }
+        }
+    }
+    found
+}
+
+/// Cell right after a word starting at `cell`
+fn end_of(cell: Cell, word: &str) -> Cell {
+    (cell.0, cell.1 + word.chars().count())
+}
+
+/// Check every phrase of the active language against `grid`
+///
+/// Each word must be found after the previous one, left to right and top to
+/// bottom. A word is ambiguous when more than one of its occurrences leaves
+/// room for the rest of the phrase.
+pub fn validate<R: AsRef<[char]>>(grid: &[R]) -> Report {
+    let mut report = Report::default();
+    let mut seen: BTreeSet<Vec<&'static str>> = BTreeSet::new();
+    let mut lit: BTreeSet<Cell> = BTreeSet::new();
+
+    for hour in 0..24 {
+        for minute in 0..60 {
+            let phrase: Vec<&'static str> = time_to_words(hour, minute)
+                .iter()
+                .map(|w| w.text())
+                .collect();
+            if !seen.insert(phrase.clone()) {
+                continue;
+            }
+            let time = (hour, minute);
+            let candidates: Vec<Vec<Cell>> =
+                phrase.iter().map(|word| occurrences(grid, word)).collect();
+
+            // Earliest placement of every word, left to right
+            let mut earliest: Vec<Cell> = Vec::with_capacity(phrase.len());
+            let mut after = (0, 0);
+            for (word, cells) in phrase.iter().zip(&candidates) {
+                match cells.iter().find(|c| **c >= after) {
+                    Some(cell) => {
+                        earliest.push(*cell);
+                        after = end_of(*cell, word);
+                    }
+                    None => break,
+                }
+            }
+            if earliest.len() < phrase.len() {
+                report.unreachable.push(Unreachable {
+                    time,
+                    phrase: phrase.clone(),
+                    word: phrase[earliest.len()],
+                });
+                continue;
+            }
+
+            // Latest placement of every word, right to left
+            let mut latest: Vec<Cell> = vec![(0, 0); phrase.len()];
+            let mut before = (usize::MAX, usize::MAX);
+            for (i, (word, cells)) in phrase.iter().zip(&candidates).enumerate().rev() {
+                // The earliest placement guarantees a match exists
+                let cell = cells
+                    .iter()
+                    .rev()
+                    .find(|c| end_of(**c, word) <= before)
+                    .unwrap();
+                latest[i] = *cell;
+                before = *cell;
+            }
+
+            for (i, (word, cells)) in phrase.iter().zip(&candidates).enumerate() {
+                let after = if i == 0 {
+                    (0, 0)
+                } else {
+                    end_of(earliest[i - 1], phrase[i - 1])
+                };
+                let before = latest
+                    .get(i + 1)
+                    .copied()
+                    .unwrap_or((usize::MAX, usize::MAX));
+                let feasible: Vec<Cell> = cells
+                    .iter()
+                    .copied()
+                    .filter(|c| *c >= after && end_of(*c, word) <= before)
+                    .collect();
+                if feasible.len() > 1 {
+                    report.ambiguous.push(Ambiguous {
+                        time,
+                        phrase: phrase.clone(),
+                        word,
+                        candidates: feasible,
+                    });
+                }
+            }
+
+            for (cell, word) in earliest.iter().zip(&phrase) {
+                for i in 0..word.chars().count() {
+                    lit.insert((cell.0, cell.1 + i));
+                }
+            }
+        }
+    }
+
+    for (row, line) in grid.iter().enumerate() {
+        for (col, c) in line.as_ref().iter().enumerate() {
+            if !lit.contains(&(row, col)) {
+                report.unused.push(((row, col), *c));
+            }
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use horloge_core::GRID;
+
+    #[test]
+    fn builtin_grid_is_valid() {
+        let report = validate(&GRID);
+        assert!(report.is_ok(), "{}", report);
+    }
+
+    #[test]
+    fn missing_word_is_unreachable() {
+        let mut grid = GRID;
+        // Move the first row to the bottom: no word can follow it
+        grid.swap(0, GRID.len() - 1);
+        let report = validate(&grid);
+        assert!(!report.is_ok());
+    }
+
+    #[test]
+    fn filler_letters_are_unused() {
+        let report = validate(&GRID);
+        assert!(!report.unused.is_empty());
+        for ((row, col), c) in &report.unused {
+            assert_eq!(GRID[*row][*col], *c);
+        }
+    }
+}